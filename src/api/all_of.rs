@@ -0,0 +1,139 @@
+use aide::openapi;
+use anyhow::{Context as _, bail, ensure};
+use indexmap::IndexMap;
+
+use crate::{JsonObject, JsonValue, utils::get_properties};
+
+use super::get_schema_name;
+
+/// An object schema with its `allOf` subschemas merged into it.
+pub(crate) struct FlattenedSchema {
+    pub schema: JsonValue,
+    /// Names of the schemas that were referenced directly from `allOf`.
+    pub parents: Vec<String>,
+}
+
+/// Merge the properties and `required` lists of all `allOf` subschemas into `schema`.
+///
+/// Subschemas can be `$ref`s to other schemas, which may use `allOf` themselves, or inline
+/// objects. Properties of the subschemas come first, in order, followed by the schema's own.
+pub(crate) fn flatten(
+    schema: &JsonValue,
+    schemas: &IndexMap<String, openapi::SchemaObject>,
+) -> anyhow::Result<FlattenedSchema> {
+    let mut parents = Vec::new();
+    let schema = flatten_inner(schema, schemas, &mut parents, &mut Vec::new())?;
+    Ok(FlattenedSchema { schema, parents })
+}
+
+fn flatten_inner(
+    schema: &JsonValue,
+    schemas: &IndexMap<String, openapi::SchemaObject>,
+    parents: &mut Vec<String>,
+    stack: &mut Vec<String>,
+) -> anyhow::Result<JsonValue> {
+    let mut result = schema
+        .as_object()
+        .context("schema must be an object")?
+        .clone();
+    let Some(all_of) = result.remove("allOf") else {
+        return Ok(schema.clone());
+    };
+    let all_of = all_of.as_array().context("allOf must be an array")?;
+
+    match result.get("type") {
+        Some(ty) => ensure!(ty == "object", "unsupported: allOf with non-object type"),
+        None => {
+            result.insert("type".to_owned(), "object".into());
+        }
+    }
+
+    let mut properties = JsonObject::new();
+    let mut required = Vec::new();
+    let mut positional = Vec::new();
+
+    for (idx, part) in all_of.iter().enumerate() {
+        let part = match get_schema_name(part["$ref"].as_str()) {
+            Some(name) => {
+                ensure!(
+                    !stack.contains(&name),
+                    "unsupported: allOf cycle through `{name}`"
+                );
+                let parent = schemas
+                    .get(&name)
+                    .with_context(|| format!("allOf[{idx}]: schema `{name}` not found"))?;
+
+                stack.push(name.clone());
+                // Parents of parents are recorded on the parent types themselves.
+                let part =
+                    flatten_inner(parent.json_schema.as_value(), schemas, &mut vec![], stack)?;
+                stack.pop();
+
+                parents.push(name);
+                part
+            }
+            None => flatten_inner(part, schemas, parents, stack)
+                .with_context(|| format!("allOf[{idx}]"))?,
+        };
+
+        for key in ["oneOf", "anyOf", "additionalProperties"] {
+            ensure!(
+                part.get(key).is_none(),
+                "allOf[{idx}]: unsupported: {key} in allOf subschema"
+            );
+        }
+        if let Some(ty) = part.get("type") {
+            ensure!(
+                ty == "object",
+                "allOf[{idx}]: unsupported: allOf subschema with non-object type"
+            );
+        }
+
+        merge_part(&part, &mut properties, &mut required, &mut positional)
+            .with_context(|| format!("allOf[{idx}]"))?;
+    }
+
+    let own = JsonValue::Object(result.clone());
+    merge_part(&own, &mut properties, &mut required, &mut positional)?;
+
+    result.insert("properties".to_owned(), properties.into());
+    result.insert("required".to_owned(), required.into());
+    if !positional.is_empty() {
+        result.insert("x-positional".to_owned(), positional.into());
+    }
+
+    Ok(result.into())
+}
+
+fn merge_part(
+    part: &JsonValue,
+    properties: &mut JsonObject,
+    required: &mut Vec<JsonValue>,
+    positional: &mut Vec<JsonValue>,
+) -> anyhow::Result<()> {
+    for (name, schema) in get_properties(part)? {
+        match properties.get(name) {
+            Some(existing) if existing != schema => {
+                bail!("conflicting definitions of property `{name}`")
+            }
+            Some(_) => {}
+            None => {
+                properties.insert(name.clone(), schema.clone());
+            }
+        }
+    }
+
+    let names = |key| part.get(key).and_then(JsonValue::as_array).into_iter();
+    for name in names("required").flatten() {
+        if !required.contains(name) {
+            required.push(name.clone());
+        }
+    }
+    for name in names("x-positional").flatten() {
+        if !positional.contains(name) {
+            positional.push(name.clone());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use aide::openapi;
+use serde::{Deserialize, Serialize};
+
+/// Metadata about the API.
+///
+/// Intermediate representation of `info` from the spec.
+#[derive(Deserialize, Serialize, PartialEq)]
+pub struct ApiInfo {
+    title: String,
+    /// Version of the API, not of the OpenAPI specification format.
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl ApiInfo {
+    pub(crate) fn from_openapi(info: openapi::Info) -> Self {
+        Self {
+            title: info.title,
+            version: info.version,
+            summary: info.summary,
+            description: info.description,
+        }
+    }
+}
+
+/// A server that hosts the API.
+#[derive(Deserialize, Serialize, PartialEq)]
+pub struct Server {
+    /// URL of the server, possibly containing `{variable}` placeholders.
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Substitutions for the placeholders in `url`.
+    #[serde(default)]
+    variables: BTreeMap<String, ServerVariable>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq)]
+pub struct ServerVariable {
+    default: String,
+    /// Allowed values, if restricted.
+    #[serde(default)]
+    values: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+pub(crate) fn servers_from_openapi(servers: Vec<openapi::Server>) -> Vec<Server> {
+    servers
+        .into_iter()
+        .map(|server| Server {
+            url: server.url,
+            description: server.description,
+            variables: server
+                .variables
+                .into_iter()
+                .map(|(name, var)| {
+                    let var = ServerVariable {
+                        default: var.default,
+                        values: var.enumeration,
+                        description: var.description,
+                    };
+                    (name, var)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// A link to additional documentation.
+#[derive(Deserialize, Serialize)]
+pub struct ExternalDocs {
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl ExternalDocs {
+    pub(crate) fn from_openapi(docs: openapi::ExternalDocumentation) -> Self {
+        Self {
+            url: docs.url,
+            description: docs.description,
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use aide::openapi;
+use heck::ToUpperCamelCase as _;
+use indexmap::IndexMap;
+use schemars::Schema;
+
+use crate::{JsonObject, JsonValue};
+
+/// Keys that describe a field rather than its type, and stay on the field when its inline schema
+/// is moved out.
+const FIELD_KEYS: &[&str] = &[
+    "description",
+    "nullable",
+    "deprecated",
+    "x-deprecated-since",
+    "x-sunset",
+    "x-replaced-by",
+    "default",
+    "example",
+    "readOnly",
+    "writeOnly",
+];
+
+/// Keys that make up a string enum, compared to decide whether two inline enums can share a
+/// schema.
+const ENUM_KEYS: &[&str] = &[
+    "enum",
+    "x-enum-varnames",
+    "x-enumNames",
+    "x-enum-descriptions",
+    "x-enum-deprecated",
+];
+
+/// Move inline object and string enum schemas of properties into their own named schemas,
+/// replacing them with `$ref`s so they can be turned into named types.
+///
+/// The new schemas are named after the `x-name` extension of the inline schema if present, or
+/// else the containing schema and the property, e.g. `EndpointInRateLimit`. Array items are
+/// named with an `Item` suffix. String enums with the same values, variant names, descriptions and
+/// deprecations share a single schema.
+pub(crate) fn hoist(schemas: &mut IndexMap<String, openapi::SchemaObject>) {
+    let mut string_enums = Vec::new();
+
+    // hoisted schemas are appended, so they get processed too
+    let mut idx = 0;
+    while let Some((parent, schema)) = schemas.get_index(idx) {
+        let parent = parent.clone();
+        let mut schema = schema.json_schema.as_value().clone();
+        let mut hoister = Hoister {
+            schemas,
+            hoisted: Vec::new(),
+            string_enums: &mut string_enums,
+            changed: false,
+        };
+        hoister.hoist_properties(&parent, &mut schema);
+        let Hoister {
+            hoisted, changed, ..
+        } = hoister;
+
+        if changed {
+            schemas[idx].json_schema = Schema::try_from(schema).expect("schema is still an object");
+            for (name, schema) in hoisted {
+                tracing::debug!(parent, name, "hoisting inline schema");
+                let schema = openapi::SchemaObject {
+                    json_schema: Schema::try_from(schema).expect("hoisted schemas are objects"),
+                    external_docs: None,
+                    example: None,
+                };
+                schemas.insert(name, schema);
+            }
+        }
+
+        idx += 1;
+    }
+}
+
+struct Hoister<'a> {
+    schemas: &'a IndexMap<String, openapi::SchemaObject>,
+    /// Schemas moved out of the current schema, with their new names.
+    hoisted: Vec<(String, JsonValue)>,
+    /// The string enums hoisted so far, as returned by `enum_key`, with the name of their schema.
+    string_enums: &'a mut Vec<(JsonObject, String)>,
+    /// Whether any inline schema of the current schema was replaced with a `$ref`, including
+    /// ones that refer to an already hoisted string enum.
+    changed: bool,
+}
+
+impl Hoister<'_> {
+    fn hoist_properties(&mut self, parent: &str, schema: &mut JsonValue) {
+        if let Some(properties) = schema
+            .get_mut("properties")
+            .and_then(JsonValue::as_object_mut)
+        {
+            for (field_name, property) in properties.iter_mut() {
+                let name = format!("{parent}{}", field_name.to_upper_camel_case());
+                if property["type"] == "array"
+                    && let Some(items) = property.get_mut("items")
+                {
+                    self.hoist_schema(items, format!("{name}Item"));
+                } else {
+                    self.hoist_schema(property, name);
+                }
+            }
+        }
+
+        // the properties of inline `allOf` subschemas end up in the same type
+        for part in schema
+            .get_mut("allOf")
+            .and_then(JsonValue::as_array_mut)
+            .into_iter()
+            .flatten()
+        {
+            self.hoist_properties(parent, part);
+        }
+    }
+
+    fn hoist_schema(&mut self, slot: &mut JsonValue, default_name: String) {
+        let is_inline_object = slot["type"] == "object"
+            && ["properties", "oneOf", "allOf"]
+                .iter()
+                .any(|key| slot.get(key).is_some());
+        // single-value enums are string constants rather than enums
+        let is_inline_enum = slot["type"] == "string"
+            && slot["enum"]
+                .as_array()
+                .is_some_and(|values| values.len() > 1);
+        if !is_inline_object && !is_inline_enum {
+            return;
+        }
+
+        let explicit_name = slot.get("x-name").and_then(JsonValue::as_str);
+        if is_inline_enum
+            && explicit_name.is_none()
+            && let Some((_, name)) = self.string_enums.iter().find(|(k, _)| *k == enum_key(slot))
+        {
+            *slot = reference(name, slot);
+            self.changed = true;
+            return;
+        }
+
+        let name = explicit_name.map_or(default_name, ToOwned::to_owned);
+        if self.schemas.contains_key(&name) || self.hoisted.iter().any(|(n, _)| *n == name) {
+            tracing::warn!(
+                name,
+                "can't hoist inline schema, a schema with that name exists"
+            );
+            return;
+        }
+
+        if is_inline_enum {
+            self.string_enums.push((enum_key(slot), name.clone()));
+        }
+        let mut schema = std::mem::replace(slot, reference(&name, slot));
+        if let Some(schema) = schema.as_object_mut() {
+            for &key in FIELD_KEYS {
+                schema.remove(key);
+            }
+        }
+        self.hoisted.push((name, schema));
+        self.changed = true;
+    }
+}
+
+/// The keys of an inline string enum schema that have to match for it to share a schema.
+fn enum_key(schema: &JsonValue) -> JsonObject {
+    ENUM_KEYS
+        .iter()
+        .filter_map(|&key| Some((key.to_owned(), schema.get(key)?.clone())))
+        .collect()
+}
+
+/// A `$ref` to the schema `name`, keeping the field-specific keys of `schema`.
+fn reference(name: &str, schema: &JsonValue) -> JsonValue {
+    let mut reference = JsonObject::new();
+    reference.insert(
+        "$ref".to_owned(),
+        format!("#/components/schemas/{name}").into(),
+    );
+    for &key in FIELD_KEYS {
+        if let Some(value) = schema.get(key) {
+            reference.insert(key.to_owned(), value.clone());
+        }
+    }
+
+    reference.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+    use schemars::Schema;
+    use serde_json::json;
+
+    use super::{hoist, openapi};
+    use crate::JsonValue;
+
+    fn schemas(schemas: JsonValue) -> IndexMap<String, openapi::SchemaObject> {
+        serde_json::from_value::<IndexMap<String, JsonValue>>(schemas)
+            .unwrap()
+            .into_iter()
+            .map(|(name, schema)| {
+                let schema = openapi::SchemaObject {
+                    json_schema: Schema::try_from(schema).unwrap(),
+                    external_docs: None,
+                    example: None,
+                };
+                (name, schema)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shared_string_enum() {
+        let status = json!({ "type": "string", "enum": ["on", "off"] });
+        let mut schemas = schemas(json!({
+            "A": { "type": "object", "properties": { "status": status } },
+            "B": { "type": "object", "properties": { "status": status } },
+        }));
+        hoist(&mut schemas);
+
+        let names: Vec<_> = schemas.keys().map(String::as_str).collect();
+        assert_eq!(names, ["A", "B", "AStatus"]);
+        for name in ["A", "B"] {
+            assert_eq!(
+                schemas[name].json_schema.as_value()["properties"]["status"],
+                json!({ "$ref": "#/components/schemas/AStatus" }),
+            );
+        }
+    }
+
+    #[test]
+    fn field_keys_stay_on_field() {
+        let mut schemas = schemas(json!({
+            "A": {
+                "type": "object",
+                "properties": {
+                    "status": {
+                        "type": "string",
+                        "enum": ["on", "off"],
+                        "description": "The status.",
+                        "deprecated": true,
+                        "x-sunset": "2030-01-01",
+                    },
+                },
+            },
+        }));
+        hoist(&mut schemas);
+
+        assert_eq!(
+            schemas["A"].json_schema.as_value()["properties"]["status"],
+            json!({
+                "$ref": "#/components/schemas/AStatus",
+                "description": "The status.",
+                "deprecated": true,
+                "x-sunset": "2030-01-01",
+            }),
+        );
+        assert_eq!(
+            *schemas["AStatus"].json_schema.as_value(),
+            json!({ "type": "string", "enum": ["on", "off"] }),
+        );
+    }
+}
@@ -1,52 +1,169 @@
-use std::collections::{BTreeSet, btree_map};
+use std::collections::{BTreeMap, BTreeSet, btree_map};
 
+pub(crate) mod all_of;
+pub(crate) mod info;
+pub(crate) mod inline_schemas;
+pub(crate) mod param_rules;
+pub(crate) mod read_write;
+pub(crate) mod recursion;
 pub(crate) mod resources;
+pub(crate) mod security;
 pub(crate) mod struct_enum;
 pub(crate) mod types;
+pub(crate) mod webhooks;
 
 use aide::openapi;
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use serde::{Deserialize, Serialize};
 
 use crate::cli_v1::IncludeMode;
 
 pub use self::{
-    resources::{Operation, Resource, Resources},
+    info::{ApiInfo, ExternalDocs, Server, ServerVariable},
+    param_rules::ParamRules,
+    resources::{
+        Callback, Operation, Pagination, Resource, Resources, ResponseStream, StreamFormat,
+    },
+    security::{
+        ApiKeyLocation, OAuth2Flow, SecurityRequirement, SecurityScheme, SecuritySchemeData,
+        SecuritySchemes,
+    },
     types::{
-        EnumVariantType, Field, FieldType, SimpleVariant, StructEnumRepr, Type, TypeData, Types,
+        Constraints, EnumVariant, EnumVariantType, Field, FieldType, SimpleVariant, StructEnumRepr,
+        Type, TypeData, Types, UnionMember,
     },
+    webhooks::Webhook,
 };
 
+/// Vendor extensions (`x-*` keys) of an object in the spec.
+pub type Extensions = BTreeMap<String, serde_json::Value>;
+
+/// Details about a deprecated operation, type or field.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Deprecation {
+    /// Version or date the deprecation happened, from `x-deprecated-since`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<String>,
+    /// Date after which it will be removed, from `x-sunset`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunset: Option<String>,
+    /// Operation ID or field name to use instead, from `x-replaced-by`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replaced_by: Option<String>,
+}
+
+impl Deprecation {
+    pub(crate) fn from_extensions(deprecated: bool, extensions: &Extensions) -> Option<Self> {
+        let get = |key| {
+            let value = extensions.get(key)?;
+            let value = value.as_str();
+            if value.is_none() {
+                tracing::warn!("{key} must be a string");
+            }
+            value.map(ToOwned::to_owned)
+        };
+
+        let deprecation = Self {
+            since: get("x-deprecated-since"),
+            sunset: get("x-sunset"),
+            replaced_by: get("x-replaced-by"),
+        };
+        if deprecated {
+            Some(deprecation)
+        } else {
+            if deprecation.since.is_some()
+                || deprecation.sunset.is_some()
+                || deprecation.replaced_by.is_some()
+            {
+                tracing::warn!("ignoring deprecation details without `deprecated: true`");
+            }
+            None
+        }
+    }
+}
+
 #[derive(Default, Deserialize, Serialize)]
 pub struct Api {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<ApiInfo>,
+    /// Servers hosting the API, in order of preference.
+    #[serde(default)]
+    pub servers: Vec<Server>,
     #[serde(with = "toplevel_resources_serde")]
     pub resources: Resources,
     pub types: Types,
+    /// Events sent to subscribers, if webhooks were included.
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    #[serde(default)]
+    pub security_schemes: SecuritySchemes,
 }
 
 impl Api {
+    /// Convert the OpenAPI spec `spec`, given as JSON, to our own representation.
     pub fn new(
-        paths: openapi::Paths,
-        components: openapi::Components,
-        webhooks: &[String],
+        spec: serde_json::Value,
+        include_webhooks: bool,
         include_mode: IncludeMode,
         excluded_operations: &BTreeSet<String>,
         specified_operations: &BTreeSet<String>,
+        param_rules: &ParamRules,
+        strict: bool,
     ) -> anyhow::Result<Self> {
-        let resources = resources::from_openapi(
-            paths,
+        let json = spec;
+        let spec = openapi::OpenApi::deserialize(&json).context("failed to parse OpenAPI spec")?;
+        let components = spec.components.unwrap_or_default();
+        let default_security = security::DefaultSecurity::new(&json, spec.security);
+        let mut resources = resources::from_openapi(
+            spec.paths.context("found no endpoints in input spec")?,
+            &default_security,
             include_mode,
             excluded_operations,
             specified_operations,
+            param_rules,
+            strict,
         )?;
-        let types = types::from_referenced_components(&resources, components.schemas, webhooks);
+        let webhooks = if include_webhooks {
+            webhooks::from_openapi(spec.webhooks, spec.extensions.get("x-webhooks"))
+        } else {
+            vec![]
+        };
+        let types = types::from_referenced_components(&resources, components.schemas, &webhooks);
+        resources::detect_pagination(&mut resources, &types);
+        let security_schemes = security::from_openapi(components.security_schemes);
+
+        Ok(Self {
+            info: Some(ApiInfo::from_openapi(spec.info)),
+            servers: info::servers_from_openapi(spec.servers),
+            resources,
+            types,
+            webhooks,
+            security_schemes,
+        })
+    }
 
-        Ok(Self { resources, types })
+    /// Replace types with read-only or write-only fields by separate input and output types.
+    pub fn split_read_write(&mut self) -> anyhow::Result<()> {
+        read_write::split(self)
     }
 
     pub(crate) fn merge(mut self, other: Self) -> anyhow::Result<Self> {
+        self.info = self.info.or(other.info);
+        for server in other.servers {
+            if !self.servers.contains(&server) {
+                self.servers.push(server);
+            }
+        }
         merge_resources(&mut self.resources, other.resources);
         merge_types(&mut self.types, other.types)?;
+        for webhook in other.webhooks {
+            if !self.webhooks.contains(&webhook) {
+                self.webhooks.push(webhook);
+            }
+        }
+        for (name, scheme) in other.security_schemes {
+            self.security_schemes.entry(name).or_insert(scheme);
+        }
         Ok(self)
     }
 }
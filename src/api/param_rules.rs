@@ -0,0 +1,75 @@
+use serde::Deserialize;
+
+/// Rules that adjust which query and header parameters end up in the generated code.
+///
+/// Rules are checked in order and the first one matching a parameter applies. There are no
+/// built-in rules, see `templates/svix-param-rules.json` for an example.
+#[derive(Default)]
+pub struct ParamRules(Vec<ParamRule>);
+
+impl ParamRules {
+    /// Parse a JSON array of rules.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(Self(serde_json::from_str(json)?))
+    }
+
+    /// Find the action for the given parameter, if any rule matches it.
+    pub(crate) fn action(
+        &self,
+        name: &str,
+        method: &str,
+        location: ParamLocation,
+    ) -> Option<&ParamAction> {
+        self.0
+            .iter()
+            .find(|rule| rule.matches(name, method, location))
+            .map(|rule| &rule.action)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParamRule {
+    /// Name of the parameter, as it appears in the spec.
+    name: String,
+    /// HTTP method of the operations to apply the rule to, or all of them if unset.
+    #[serde(default)]
+    method: Option<String>,
+    /// Location of the parameter to apply the rule to, or any location if unset.
+    #[serde(default)]
+    location: Option<ParamLocation>,
+    action: ParamAction,
+}
+
+impl ParamRule {
+    fn matches(&self, name: &str, method: &str, location: ParamLocation) -> bool {
+        self.name == name
+            && self
+                .method
+                .as_ref()
+                .is_none_or(|m| m.eq_ignore_ascii_case(method))
+            && self.location.is_none_or(|l| l == location)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ParamLocation {
+    Query,
+    Header,
+}
+
+/// What to do with a matching parameter.
+///
+/// Encoded as `"drop"`, `"include"` or `{"rename": "new_name"}`.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ParamAction {
+    /// Leave the parameter out of the generated code.
+    Drop,
+    /// Keep sending the parameter under its name from the spec, but call it differently in the
+    /// generated code.
+    Rename(String),
+    /// Include the parameter without warning about it being unknown.
+    Include,
+}
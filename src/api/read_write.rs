@@ -0,0 +1,120 @@
+use std::collections::BTreeSet;
+
+use anyhow::ensure;
+
+use super::{Api, recursion, resources};
+
+/// The direction a type is sent in.
+#[derive(Clone, Copy)]
+pub(crate) enum Direction {
+    /// From the client to the server, e.g. as a request body. Read-only fields are left out.
+    Input,
+    /// From the server to the client, e.g. as a response body. Write-only fields are left out.
+    Output,
+}
+
+impl Direction {
+    fn type_name(self, name: &str) -> String {
+        match self {
+            Direction::Input => format!("{name}Input"),
+            Direction::Output => format!("{name}Output"),
+        }
+    }
+}
+
+/// Replace types that have read-only or write-only fields with an input and an output type,
+/// named with `Input` and `Output` suffixes, and use the matching one wherever they're used.
+///
+/// Types that refer to a replaced type are replaced as well, so that their input and output
+/// types can refer to the matching variant.
+pub(crate) fn split(api: &mut Api) -> anyhow::Result<()> {
+    let mut split: BTreeSet<String> = api
+        .types
+        .iter()
+        .filter(|(_, ty)| ty.has_read_write_only_fields())
+        .map(|(name, _)| name.clone())
+        .collect();
+    loop {
+        let referring: Vec<_> = api
+            .types
+            .iter()
+            .filter(|(name, ty)| {
+                !split.contains(*name)
+                    && ty
+                        .referenced_components()
+                        .into_iter()
+                        .any(|c| split.contains(c))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        if referring.is_empty() {
+            break;
+        }
+        split.extend(referring);
+    }
+
+    for name in &split {
+        for direction in [Direction::Input, Direction::Output] {
+            let new_name = direction.type_name(name);
+            ensure!(
+                !api.types.contains_key(&new_name),
+                "can't split `{name}` into input and output types, `{new_name}` already exists"
+            );
+        }
+    }
+
+    let rename = |name: &mut String, direction: Direction| {
+        if split.contains(name) {
+            *name = direction.type_name(name);
+        }
+    };
+
+    let mut new_names = Vec::new();
+    for name in &split {
+        let ty = api.types.remove(name).expect("split types exist");
+        for direction in [Direction::Input, Direction::Output] {
+            let new_name = direction.type_name(name);
+            tracing::debug!(name, new_name, "splitting type");
+
+            let mut new_ty = ty.for_direction(new_name.clone(), direction);
+            new_ty.for_each_schema_name_mut(&mut |name| rename(name, direction));
+            api.types.insert(new_name.clone(), new_ty);
+            new_names.push(new_name);
+        }
+    }
+
+    for resource in api.resources.values_mut() {
+        resource.for_each_schema_name_mut(&mut |name, direction| rename(name, direction));
+    }
+    for webhook in &mut api.webhooks {
+        rename(&mut webhook.payload_schema_name, Direction::Output);
+    }
+
+    // Not every type is used in both directions.
+    let used = used_types(api);
+    for name in new_names {
+        if !used.contains(&name) {
+            api.types.remove(&name);
+        }
+    }
+    // Leaving out fields can break cycles.
+    recursion::mark_recursive_refs(&mut api.types);
+
+    Ok(())
+}
+
+/// Names of the types used by operations and webhooks, directly or through other types.
+fn used_types(api: &Api) -> BTreeSet<String> {
+    let mut pending: Vec<&str> = resources::referenced_components(&api.resources)
+        .chain(api.webhooks.iter().map(|w| w.payload_schema_name.as_str()))
+        .collect();
+    let mut used = BTreeSet::new();
+    while let Some(name) = pending.pop() {
+        if used.insert(name.to_owned())
+            && let Some(ty) = api.types.get(name)
+        {
+            pending.extend(ty.referenced_components());
+        }
+    }
+    used
+}
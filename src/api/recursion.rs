@@ -0,0 +1,45 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Types;
+
+/// Set the `recursive` flag of every field, struct enum variant and union member whose type
+/// contains the type it's part of by value, directly or through other types.
+///
+/// Lists, sets and maps don't count, since they store their items out of line in every
+/// language we generate code for.
+pub(crate) fn mark_recursive_refs(types: &mut Types) {
+    let mut contains = BTreeMap::new();
+    for (name, ty) in types.iter_mut() {
+        let mut targets = BTreeSet::new();
+        ty.for_each_direct_ref_mut(&mut |target, _| {
+            targets.insert(target.to_owned());
+        });
+        contains.insert(name.clone(), targets);
+    }
+
+    for (name, ty) in types.iter_mut() {
+        ty.for_each_direct_ref_mut(&mut |target, recursive| {
+            *recursive = reaches(&contains, target, name);
+            if *recursive {
+                tracing::debug!(name, target, "found recursive type reference");
+            }
+        });
+    }
+}
+
+/// Whether the type `from` contains the type `to` by value, possibly through other types.
+fn reaches(contains: &BTreeMap<String, BTreeSet<String>>, from: &str, to: &str) -> bool {
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![from];
+    while let Some(name) = pending.pop() {
+        if name == to {
+            return true;
+        }
+        if visited.insert(name)
+            && let Some(targets) = contains.get(name)
+        {
+            pending.extend(targets.iter().map(String::as_str));
+        }
+    }
+    false
+}
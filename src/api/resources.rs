@@ -1,17 +1,22 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
+    fmt,
     ops::Deref,
 };
 
 use aide::openapi::{self, ReferenceOr};
-use anyhow::{Context as _, bail};
+use anyhow::{Context as _, bail, ensure};
 use serde::{Deserialize, Serialize};
 
-use crate::cli_v1::IncludeMode;
+use crate::{JsonValue, cli_v1::IncludeMode};
 
 use super::{
-    get_schema_name,
-    types::{FieldType, serialize_field_type},
+    Deprecation, Extensions, get_schema_name,
+    info::{self, ExternalDocs, Server},
+    param_rules::{ParamAction, ParamLocation, ParamRules},
+    read_write::Direction,
+    security::{self, DefaultSecurity, SecurityRequirement},
+    types::{FieldType, TypeData, Types, serialize_field_type},
 };
 
 /// The API operations of the API client we generate.
@@ -21,9 +26,12 @@ pub type Resources = BTreeMap<String, Resource>;
 
 pub(crate) fn from_openapi(
     paths: openapi::Paths,
+    default_security: &DefaultSecurity,
     include_mode: IncludeMode,
     excluded_operations: &BTreeSet<String>,
     specified_operations: &BTreeSet<String>,
+    param_rules: &ParamRules,
+    strict: bool,
 ) -> anyhow::Result<Resources> {
     let mut resources = BTreeMap::new();
 
@@ -38,16 +46,23 @@ pub(crate) fn from_openapi(
         }
 
         for (method, op) in path_item {
-            if let Some((res_path, op)) = Operation::from_openapi(
+            match Operation::from_openapi(
                 &path,
                 method,
                 op,
                 include_mode,
                 excluded_operations,
                 specified_operations,
+                param_rules,
             ) {
-                let resource = get_or_insert_resource(&mut resources, res_path);
-                resource.operations.push(op);
+                Ok(Some((res_path, mut op))) => {
+                    op.security = default_security.apply(&path, method, op.security);
+                    let resource = get_or_insert_resource(&mut resources, res_path);
+                    resource.operations.push(op);
+                }
+                Ok(None) => {}
+                Err(violation) if strict => return Err(violation.into()),
+                Err(violation) => tracing::warn!("skipping operation: {violation}"),
             }
         }
     }
@@ -55,6 +70,16 @@ pub(crate) fn from_openapi(
     Ok(resources)
 }
 
+/// Annotate list operations with their [`Pagination`], now that all types are known.
+pub(crate) fn detect_pagination(resources: &mut Resources, types: &Types) {
+    for resource in resources.values_mut() {
+        for op in &mut resource.operations {
+            op.pagination = op.detect_pagination(types);
+        }
+        detect_pagination(&mut resource.subresources, types);
+    }
+}
+
 pub(crate) fn referenced_components(resources: &Resources) -> impl Iterator<Item = &str> {
     resources.values().flat_map(Resource::referenced_components)
 }
@@ -115,11 +140,56 @@ impl Resource {
             if let Some(name) = &operation.response_body_schema_name {
                 res.insert(name);
             }
+            if let Some(stream) = &operation.response_stream {
+                res.insert(&stream.item_schema_name);
+            }
+            for callback in &operation.callbacks {
+                res.extend(callback.referenced_components());
+            }
         }
 
         res
     }
 
+    /// Call `f` with the name of every type used by the operations of this resource and its
+    /// subresources, along with the direction the type is sent in.
+    pub(crate) fn for_each_schema_name_mut(&mut self, f: &mut impl FnMut(&mut String, Direction)) {
+        for resource in self.subresources.values_mut() {
+            resource.for_each_schema_name_mut(f);
+        }
+
+        for operation in &mut self.operations {
+            for param in &mut operation.query_params {
+                param
+                    .r#type
+                    .for_each_schema_name_mut(&mut |name| f(name, Direction::Input));
+            }
+            if let Some(name) = &mut operation.request_body_schema_name {
+                f(name, Direction::Input);
+            }
+            if let Some(name) = &mut operation.response_body_schema_name {
+                f(name, Direction::Output);
+            }
+            if let Some(stream) = &mut operation.response_stream {
+                f(&mut stream.item_schema_name, Direction::Output);
+            }
+            if let Some(pagination) = &mut operation.pagination {
+                pagination
+                    .item_type
+                    .for_each_schema_name_mut(&mut |name| f(name, Direction::Output));
+            }
+            // Callbacks are requests from the server to the client.
+            for callback in &mut operation.callbacks {
+                if let Some(name) = &mut callback.request_body_schema_name {
+                    f(name, Direction::Output);
+                }
+                if let Some(name) = &mut callback.response_body_schema_name {
+                    f(name, Direction::Input);
+                }
+            }
+        }
+    }
+
     pub(crate) fn referenced_components_direct(&self) -> impl Iterator<Item = &str> {
         self.operations.iter().flat_map(|op| {
             op.query_params
@@ -130,6 +200,16 @@ impl Resource {
                 })
                 .chain(op.request_body_schema_name.iter().map(Deref::deref))
                 .chain(op.response_body_schema_name.iter().map(Deref::deref))
+                .chain(
+                    op.response_stream
+                        .iter()
+                        .map(|s| s.item_schema_name.as_str()),
+                )
+                .chain(
+                    op.callbacks
+                        .iter()
+                        .flat_map(Callback::referenced_components),
+                )
         })
     }
 }
@@ -141,17 +221,32 @@ pub struct Operation {
     pub(crate) id: String,
     /// The name to use for the operation in code.
     pub(crate) name: String,
+    /// One-line summary of the operation to use for documentation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
     /// Description of the operation to use for documentation.
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    /// Tags for grouping the operation in documentation.
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Link to additional documentation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_docs: Option<ExternalDocs>,
     /// Whether this operation is marked as deprecated.
     deprecated: bool,
+    /// Details about the deprecation, if deprecated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecation: Option<Deprecation>,
     /// The HTTP method.
     ///
     /// Encoded as "get", "post" or such because that's what aide's PathItem iterator gives us.
     method: String,
     /// The operation's endpoint path.
     path: String,
+    /// Servers that host this operation, overriding the API's servers if not empty.
+    #[serde(default)]
+    servers: Vec<Server>,
     /// Path parameters.
     ///
     /// Only required string-typed parameters are currently supported.
@@ -165,9 +260,33 @@ pub struct Operation {
     /// Name of the request body type, if any.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) request_body_schema_name: Option<String>,
+    /// Whether the request body may be omitted.
+    #[serde(default)]
+    request_body_optional: bool,
     /// Name of the response body type, if any.
     #[serde(skip_serializing_if = "Option::is_none")]
     response_body_schema_name: Option<String>,
+    /// The stream of items returned by the operation, if it streams its response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_stream: Option<ResponseStream>,
+    /// Requests the API sends to the client as a result of this operation.
+    #[serde(default)]
+    callbacks: Vec<Callback>,
+    /// Alternative security requirements, any one of which authorizes a request.
+    ///
+    /// Falls back to the spec's top-level requirements if the operation doesn't declare any.
+    /// Empty if the operation allows anonymous access, e.g. with `security: []`.
+    #[serde(default)]
+    security: Vec<SecurityRequirement>,
+    /// Pagination of the response, if this is a paginated list operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagination: Option<Pagination>,
+    /// How to detect pagination, from the `x-pagination` extension.
+    #[serde(skip)]
+    pagination_hint: PaginationHint,
+    /// Vendor extensions of the operation.
+    #[serde(default)]
+    extensions: Extensions,
 }
 
 impl Operation {
@@ -183,10 +302,11 @@ impl Operation {
         include_mode: IncludeMode,
         excluded_operations: &BTreeSet<String>,
         specified_operations: &BTreeSet<String>,
-    ) -> Option<(Vec<String>, Self)> {
+        param_rules: &ParamRules,
+    ) -> Result<Option<(Vec<String>, Self)>, SpecViolation> {
         let Some(op_id) = op.operation_id else {
             // ignore operations without an operationId
-            return None;
+            return Ok(None);
         };
         tracing::Span::current().record("op_id", &op_id);
 
@@ -203,7 +323,7 @@ impl Operation {
             IncludeMode::OnlySpecified => is_specified,
         };
         if !include_operation || excluded_operations.contains(&op_id) {
-            return None;
+            return Ok(None);
         }
 
         let mut op_id_parts_iter = op_id.split('.');
@@ -212,38 +332,61 @@ impl Operation {
             .expect("split iter always contains at least one item");
         let Some(op_name) = op_id_parts_iter.next_back() else {
             tracing::debug!("skipping operation whose ID doesn't contain a period");
-            return None;
+            return Ok(None);
         };
 
         let res_path: Vec<_> = op_id_parts_iter.map(ToOwned::to_owned).collect();
         if res_path.is_empty() {
             tracing::debug!("skipping operation whose ID only contains one period");
-            return None;
+            return Ok(None);
         }
 
         if version != "v1" {
             tracing::warn!("found operation whose ID does not begin with v1");
-            return None;
+            return Ok(None);
         }
 
+        let loc = OperationLocation::new(&op_id, path, method);
+
+        let pagination_hint = match op.extensions.get("x-pagination") {
+            None => PaginationHint::Convention,
+            Some(JsonValue::Bool(false)) => PaginationHint::Disabled,
+            Some(JsonValue::Bool(true)) => PaginationHint::Explicit(PaginationFields::default()),
+            Some(value) => match PaginationFields::deserialize(value) {
+                Ok(fields) => PaginationHint::Explicit(fields),
+                Err(e) => {
+                    tracing::debug!("invalid x-pagination extension: {e}");
+                    return Err(loc.violation(
+                        "/x-pagination",
+                        "x-pagination must be a boolean or an object of field names",
+                    ));
+                }
+            },
+        };
+
         let mut path_params = Vec::new();
         let mut query_params = Vec::new();
         let mut header_params = Vec::new();
 
-        for param in op.parameters {
+        for (idx, param) in op.parameters.into_iter().enumerate() {
             match param {
                 ReferenceOr::Reference { .. } => {
                     tracing::warn!("$ref parameters are not currently supported");
-                    return None;
+                    return Ok(None);
                 }
                 ReferenceOr::Item(openapi::Parameter::Path {
                     parameter_data,
                     style: openapi::PathStyle::Simple,
                 }) => {
-                    assert!(parameter_data.required, "no optional path params");
+                    if !parameter_data.required {
+                        return Err(loc.violation(
+                            &format!("/parameters/{idx}/required"),
+                            "path parameters must be required",
+                        ));
+                    }
                     if let Err(e) = enforce_string_parameter(&parameter_data) {
                         tracing::warn!("unsupported path parameter: {e}");
-                        return None;
+                        return Ok(None);
                     }
 
                     path_params.push(parameter_data.name);
@@ -252,18 +395,36 @@ impl Operation {
                     parameter_data,
                     style: openapi::HeaderStyle::Simple,
                 }) => {
-                    if parameter_data.name != "idempotency-key" {
-                        tracing::warn!(name = parameter_data.name, "unknown header parameter");
-                    }
+                    let code_name = match param_rules.action(
+                        &parameter_data.name,
+                        method,
+                        ParamLocation::Header,
+                    ) {
+                        Some(ParamAction::Drop) => {
+                            tracing::debug!(
+                                name = parameter_data.name,
+                                "dropping header parameter"
+                            );
+                            continue;
+                        }
+                        Some(ParamAction::Rename(code_name)) => Some(code_name.clone()),
+                        Some(ParamAction::Include) => None,
+                        None => {
+                            tracing::warn!(name = parameter_data.name, "unknown header parameter");
+                            None
+                        }
+                    };
 
                     if let Err(e) = enforce_string_parameter(&parameter_data) {
                         tracing::warn!("unsupported header parameter: {e}");
-                        return None;
+                        return Ok(None);
                     }
 
                     header_params.push(HeaderParam {
                         name: parameter_data.name,
+                        code_name,
                         required: parameter_data.required,
+                        extensions: parameter_data.extensions.into_iter().collect(),
                     });
                 }
                 ReferenceOr::Item(openapi::Parameter::Query {
@@ -273,25 +434,31 @@ impl Operation {
                     allow_empty_value: None,
                 }) => {
                     let name = parameter_data.name;
-                    if method == "post" && name == "get_if_exists" {
-                        tracing::debug!("ignoring get_if_exists query parameter");
-                        continue;
-                    }
+                    let code_name = match param_rules.action(&name, method, ParamLocation::Query) {
+                        Some(ParamAction::Drop) => {
+                            tracing::debug!(name, "dropping query parameter");
+                            continue;
+                        }
+                        Some(ParamAction::Rename(code_name)) => Some(code_name.clone()),
+                        Some(ParamAction::Include) | None => None,
+                    };
 
                     let _guard = tracing::info_span!("field_type_from_openapi", name).entered();
                     let r#type = match FieldType::from_openapi(parameter_data.format) {
                         Ok(t) => t,
                         Err(e) => {
                             tracing::warn!("unsupported query parameter type: {e}");
-                            return None;
+                            return Ok(None);
                         }
                     };
 
                     query_params.push(QueryParam {
                         name,
+                        code_name,
                         description: parameter_data.description,
                         required: parameter_data.required,
                         r#type,
+                        extensions: parameter_data.extensions.into_iter().collect(),
                     });
                 }
                 ReferenceOr::Item(parameter) => {
@@ -299,83 +466,341 @@ impl Operation {
                         ?parameter,
                         "this kind of parameter is not currently supported"
                     );
-                    return None;
+                    return Ok(None);
                 }
             }
         }
 
-        let request_body_schema_name = match op.request_body {
-            Some(x) => match x {
-                ReferenceOr::Item(mut req_body) => {
-                    assert!(req_body.required);
-                    assert!(req_body.extensions.is_empty());
-                    assert_eq!(req_body.content.len(), 1);
-                    let Some(json_body) = req_body.content.swap_remove("application/json") else {
-                        tracing::error!("request should have JSON body");
-                        return None;
-                    };
-                    assert!(json_body.extensions.is_empty());
-                    get_body_schema_name(json_body)
-                }
-                ReferenceOr::Reference { .. } => {
-                    tracing::error!("$ref request bodies are not currently supported");
-                    return None;
-                }
+        let (request_body_schema_name, request_body_optional) = match op.request_body {
+            Some(body) => match request_body(body, &loc)? {
+                Some(RequestBody {
+                    schema_name,
+                    optional,
+                }) => (schema_name, optional),
+                None => return Ok(None),
             },
-            None => None,
+            None => (None, false),
         };
 
-        let response_body_schema_name = op.responses.and_then(|r| {
-            assert_eq!(r.default, None);
-            assert!(r.extensions.is_empty());
-            let mut success_responses = r.responses.into_iter().filter(|(st, _)| {
-                match st {
-                    openapi::StatusCode::Code(c) => match c {
-                        0..100 => tracing::error!("invalid status code < 100"),
-                        100..200 => tracing::error!("what is this? status code {c}..."),
-                        200..300 => return true,
-                        300..400 => tracing::error!("what is this? status code {c}..."),
-                        400.. => {}
-                    },
-                    openapi::StatusCode::Range(_) => {
-                        tracing::error!("unsupported status code range");
+        let (response_body_schema_name, response_stream) = match op.responses {
+            Some(r) => match response_body(r, &loc)? {
+                Some(ResponseBody::Json(name)) => (Some(name), None),
+                Some(ResponseBody::Stream(stream)) => (None, Some(stream)),
+                None => (None, None),
+            },
+            None => (None, None),
+        };
+
+        let mut callbacks = Vec::new();
+        for (name, callback) in op.callbacks {
+            let ReferenceOr::Item(callback) = callback else {
+                tracing::warn!(name, "$ref callbacks are not currently supported");
+                continue;
+            };
+            for (expression, item) in callback {
+                let ReferenceOr::Item(item) = item else {
+                    tracing::warn!(name, "$ref callback path items are not currently supported");
+                    continue;
+                };
+                for (method, cb_op) in item {
+                    let cb_loc = loc.nested(&format!(
+                        "/callbacks/{}/{}/{method}",
+                        escape_json_pointer(&name),
+                        escape_json_pointer(&expression),
+                    ));
+                    if let Some(cb) =
+                        Callback::from_openapi(&name, &expression, method, cb_op, &cb_loc)?
+                    {
+                        callbacks.push(cb);
                     }
                 }
-
-                false
-            });
-
-            let (_, resp) = success_responses
-                .next()
-                .expect("every operation must have one success response");
-            let schema_name = response_body_schema_name(resp);
-            for (_, resp) in success_responses {
-                assert_eq!(schema_name, response_body_schema_name(resp));
             }
+        }
 
-            schema_name
-        });
+        let extensions: Extensions = op.extensions.into_iter().collect();
+        let deprecation = Deprecation::from_extensions(op.deprecated, &extensions);
 
         let op_name = op_name.to_owned();
         let op = Operation {
             id: op_id,
             name: op_name,
+            summary: op.summary,
             description: op.description,
+            tags: op.tags,
+            external_docs: op.external_docs.map(ExternalDocs::from_openapi),
             deprecated: op.deprecated,
+            deprecation,
             method: method.to_owned(),
             path: path.to_owned(),
+            servers: info::servers_from_openapi(op.servers),
             path_params,
             header_params,
             query_params,
             request_body_schema_name,
+            request_body_optional,
             response_body_schema_name,
+            response_stream,
+            callbacks,
+            security: security::requirements_from_openapi(op.security),
+            pagination: None,
+            pagination_hint,
+            extensions,
         };
-        Some((res_path, op))
+        Ok(Some((res_path, op)))
     }
 
     pub(crate) fn has_query_or_header_params(&self) -> bool {
         !self.header_params.is_empty() || !self.query_params.is_empty()
     }
+
+    fn detect_pagination(&self, types: &Types) -> Option<Pagination> {
+        let convention;
+        let fields = match &self.pagination_hint {
+            PaginationHint::Disabled => return None,
+            // Only list operations are paginated by convention
+            PaginationHint::Convention if self.method != "get" => return None,
+            PaginationHint::Convention => {
+                convention = PaginationFields::default();
+                &convention
+            }
+            PaginationHint::Explicit(fields) => fields,
+        };
+
+        match Pagination::new(self, fields, types) {
+            Ok(pagination) => Some(pagination),
+            Err(e) => {
+                if let PaginationHint::Explicit(_) = self.pagination_hint {
+                    tracing::warn!(op_id = self.id, "ignoring x-pagination: {e:#}");
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Cursor-based pagination of a list operation.
+#[derive(Deserialize, Serialize)]
+pub struct Pagination {
+    /// Query parameter that takes the cursor of the page to fetch.
+    cursor_param: String,
+    /// Query parameter that limits the number of items per page, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_param: Option<String>,
+    /// Response field that holds the items of the page.
+    items_field: String,
+    /// Type of the page items.
+    #[serde(serialize_with = "serialize_field_type")]
+    item_type: FieldType,
+    /// Response field that holds the cursor of the next page.
+    next_cursor_field: String,
+    /// Response field that holds the cursor of the previous page, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev_cursor_field: Option<String>,
+    /// Response field that is `true` once the last page was returned.
+    done_field: String,
+}
+
+impl Pagination {
+    fn new(op: &Operation, names: &PaginationFields, types: &Types) -> anyhow::Result<Self> {
+        let has_query_param = |name: &str| op.query_params.iter().any(|p| p.name == name);
+        ensure!(
+            has_query_param(&names.cursor_param),
+            "missing cursor query parameter `{}`",
+            names.cursor_param
+        );
+
+        let response_ty = op
+            .response_body_schema_name
+            .as_ref()
+            .and_then(|name| types.get(name))
+            .context("missing response body type")?;
+        let TypeData::Struct { fields, .. } = &response_ty.data else {
+            bail!("response body type must be a struct");
+        };
+        let get_field = |name: &str| fields.iter().find(|f| f.name == name);
+
+        let items = get_field(&names.items_field)
+            .with_context(|| format!("missing items field `{}`", names.items_field))?;
+        let FieldType::List { inner: item_type } = &items.r#type else {
+            bail!("items field `{}` must be a list", names.items_field);
+        };
+        ensure!(
+            get_field(&names.next_cursor_field).is_some(),
+            "missing next cursor field `{}`",
+            names.next_cursor_field
+        );
+        let done = get_field(&names.done_field)
+            .with_context(|| format!("missing done field `{}`", names.done_field))?;
+        ensure!(
+            done.r#type == FieldType::Bool,
+            "done field `{}` must be a boolean",
+            names.done_field
+        );
+
+        Ok(Self {
+            cursor_param: names.cursor_param.clone(),
+            limit_param: has_query_param(&names.limit_param).then(|| names.limit_param.clone()),
+            items_field: names.items_field.clone(),
+            item_type: (**item_type).clone(),
+            next_cursor_field: names.next_cursor_field.clone(),
+            prev_cursor_field: get_field(&names.prev_cursor_field)
+                .map(|_| names.prev_cursor_field.clone()),
+            done_field: names.done_field.clone(),
+        })
+    }
+}
+
+#[derive(Default)]
+enum PaginationHint {
+    /// Detect pagination of GET operations by field names.
+    #[default]
+    Convention,
+    /// `x-pagination` is an object of field names, or `true` to use the default names.
+    Explicit(PaginationFields),
+    /// `x-pagination: false`.
+    Disabled,
+}
+
+/// Names of the parameters and fields used by a paginated operation.
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+struct PaginationFields {
+    cursor_param: String,
+    limit_param: String,
+    items_field: String,
+    next_cursor_field: String,
+    prev_cursor_field: String,
+    done_field: String,
+}
+
+impl Default for PaginationFields {
+    fn default() -> Self {
+        Self {
+            cursor_param: "iterator".to_owned(),
+            limit_param: "limit".to_owned(),
+            items_field: "data".to_owned(),
+            next_cursor_field: "iterator".to_owned(),
+            prev_cursor_field: "prevIterator".to_owned(),
+            done_field: "done".to_owned(),
+        }
+    }
+}
+
+/// A request sent by the API to a URL provided by the client.
+///
+/// Intermediate representation of an operation in `callbacks`.
+#[derive(Deserialize, Serialize)]
+pub struct Callback {
+    /// Name of the callback in the spec.
+    name: String,
+    /// Runtime expression that evaluates to the request URL, like `{$request.body#/url}`.
+    expression: String,
+    /// The HTTP method.
+    method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    deprecated: bool,
+    /// Name of the request body type, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_body_schema_name: Option<String>,
+    /// Name of the response body type the client is expected to reply with, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_body_schema_name: Option<String>,
+}
+
+impl Callback {
+    /// Returns `Ok(None)` if the callback uses unsupported features and should be skipped.
+    fn from_openapi(
+        name: &str,
+        expression: &str,
+        method: &str,
+        op: openapi::Operation,
+        loc: &OperationLocation<'_>,
+    ) -> Result<Option<Self>, SpecViolation> {
+        let request_body_schema_name = match op.request_body {
+            Some(body) => match request_body(body, loc)? {
+                Some(body) => body.schema_name,
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
+        let response_body_schema_name = match op.responses {
+            Some(r) => match response_body(r, loc)? {
+                Some(ResponseBody::Json(name)) => Some(name),
+                Some(ResponseBody::Stream(_)) => {
+                    tracing::warn!(name, "streaming callback responses are not supported");
+                    return Ok(None);
+                }
+                None => None,
+            },
+            None => None,
+        };
+
+        Ok(Some(Self {
+            name: name.to_owned(),
+            expression: expression.to_owned(),
+            method: method.to_owned(),
+            summary: op.summary,
+            description: op.description,
+            deprecated: op.deprecated,
+            request_body_schema_name,
+            response_body_schema_name,
+        }))
+    }
+
+    fn referenced_components(&self) -> impl Iterator<Item = &str> {
+        self.request_body_schema_name
+            .iter()
+            .chain(&self.response_body_schema_name)
+            .map(Deref::deref)
+    }
+}
+
+struct RequestBody {
+    schema_name: Option<String>,
+    optional: bool,
+}
+
+/// Returns `Ok(None)` if the request body is unsupported and the operation should be skipped.
+fn request_body(
+    body: ReferenceOr<openapi::RequestBody>,
+    loc: &OperationLocation<'_>,
+) -> Result<Option<RequestBody>, SpecViolation> {
+    let mut req_body = match body {
+        ReferenceOr::Item(req_body) => req_body,
+        ReferenceOr::Reference { .. } => {
+            tracing::error!("$ref request bodies are not currently supported");
+            return Ok(None);
+        }
+    };
+
+    if !req_body.extensions.is_empty() {
+        return Err(loc.violation("/requestBody", "request bodies must not have extensions"));
+    }
+    if req_body.content.len() != 1 {
+        return Err(loc.violation(
+            "/requestBody/content",
+            "request bodies must have exactly one media type",
+        ));
+    }
+    let Some(json_body) = req_body.content.swap_remove("application/json") else {
+        tracing::error!("request should have JSON body");
+        return Ok(None);
+    };
+    if !json_body.extensions.is_empty() {
+        return Err(loc.violation(
+            "/requestBody/content/application~1json",
+            "media types must not have extensions",
+        ));
+    }
+
+    Ok(Some(RequestBody {
+        schema_name: get_body_schema_name(json_body),
+        optional: !req_body.required,
+    }))
 }
 
 fn get_body_schema_name(json_body: openapi::MediaType) -> Option<String> {
@@ -412,41 +837,279 @@ fn enforce_string_parameter(parameter_data: &openapi::ParameterData) -> anyhow::
     Ok(())
 }
 
-fn response_body_schema_name(resp: ReferenceOr<openapi::Response>) -> Option<String> {
+fn response_body(
+    responses: openapi::Responses,
+    loc: &OperationLocation<'_>,
+) -> Result<Option<ResponseBody>, SpecViolation> {
+    if responses.default.is_some() {
+        return Err(loc.violation("/responses/default", "default responses are not supported"));
+    }
+    if !responses.extensions.is_empty() {
+        return Err(loc.violation("/responses", "responses must not have extensions"));
+    }
+
+    let mut success_responses = responses.responses.into_iter().filter(|(st, _)| {
+        match st {
+            openapi::StatusCode::Code(c) => match c {
+                0..100 => tracing::error!("invalid status code < 100"),
+                100..200 => tracing::error!("what is this? status code {c}..."),
+                200..300 => return true,
+                300..400 => tracing::error!("what is this? status code {c}..."),
+                400.. => {}
+            },
+            openapi::StatusCode::Range(_) => {
+                tracing::error!("unsupported status code range");
+            }
+        }
+
+        false
+    });
+
+    let Some((status, resp)) = success_responses.next() else {
+        return Err(loc.violation(
+            "/responses",
+            "every operation must have one success response",
+        ));
+    };
+    let body = success_response_body(resp, &status, loc)?;
+    for (status, resp) in success_responses {
+        if success_response_body(resp, &status, loc)? != body {
+            return Err(loc.violation(
+                &format!("/responses/{status}"),
+                "all success responses must have the same body schema",
+            ));
+        }
+    }
+
+    Ok(body)
+}
+
+fn success_response_body(
+    resp: ReferenceOr<openapi::Response>,
+    status: &openapi::StatusCode,
+    loc: &OperationLocation<'_>,
+) -> Result<Option<ResponseBody>, SpecViolation> {
     match resp {
-        ReferenceOr::Item(mut resp_body) => {
-            assert!(resp_body.extensions.is_empty());
+        ReferenceOr::Item(resp_body) => {
+            if !resp_body.extensions.is_empty() {
+                return Err(loc.violation(
+                    &format!("/responses/{status}"),
+                    "responses must not have extensions",
+                ));
+            }
             if resp_body.content.is_empty() {
-                return None;
+                return Ok(None);
             }
 
-            assert_eq!(resp_body.content.len(), 1);
-            let Some(json_body) = resp_body.content.swap_remove("application/json") else {
-                tracing::error!("response should have JSON body");
-                return None;
+            if resp_body.content.len() != 1 {
+                return Err(loc.violation(
+                    &format!("/responses/{status}/content"),
+                    "responses must have at most one media type",
+                ));
+            }
+            let (media_type, body) = resp_body
+                .content
+                .into_iter()
+                .next()
+                .expect("content has exactly one entry");
+            if !body.extensions.is_empty() {
+                return Err(loc.violation(
+                    &format!(
+                        "/responses/{status}/content/{}",
+                        escape_json_pointer(&media_type)
+                    ),
+                    "media types must not have extensions",
+                ));
+            }
+
+            let format = match media_type.as_str() {
+                "application/json" => {
+                    return Ok(get_body_schema_name(body).map(ResponseBody::Json));
+                }
+                "text/event-stream" => StreamFormat::Sse,
+                "application/x-ndjson" => StreamFormat::Ndjson,
+                _ => {
+                    tracing::error!(media_type, "response should have JSON or streaming body");
+                    return Ok(None);
+                }
             };
-            assert!(json_body.extensions.is_empty());
-            get_body_schema_name(json_body)
+            Ok(get_body_schema_name(body).map(|item_schema_name| {
+                ResponseBody::Stream(ResponseStream {
+                    format,
+                    item_schema_name,
+                })
+            }))
         }
         ReferenceOr::Reference { .. } => {
             tracing::error!("$ref response bodies are not currently supported");
-            None
+            Ok(None)
+        }
+    }
+}
+
+/// Body of a success response.
+#[derive(PartialEq)]
+enum ResponseBody {
+    /// A JSON document of the named type.
+    Json(String),
+    Stream(ResponseStream),
+}
+
+/// A response body that is a stream of items, rather than a single document.
+#[derive(Deserialize, Serialize, PartialEq)]
+pub struct ResponseStream {
+    format: StreamFormat,
+    /// Name of the type of the stream items.
+    pub(crate) item_schema_name: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamFormat {
+    /// Server-Sent Events (`text/event-stream`), with an item in the `data` of each event.
+    Sse,
+    /// Newline-delimited JSON (`application/x-ndjson`), with one item per line.
+    Ndjson,
+}
+
+/// Location of an operation in the spec, used to report [`SpecViolation`]s.
+struct OperationLocation<'a> {
+    op_id: &'a str,
+    /// JSON pointer to the operation object.
+    pointer: String,
+}
+
+impl<'a> OperationLocation<'a> {
+    fn new(op_id: &'a str, path: &str, method: &str) -> Self {
+        Self {
+            op_id,
+            pointer: format!("/paths/{}/{method}", escape_json_pointer(path)),
+        }
+    }
+
+    /// Location of an object nested inside the operation object.
+    fn nested(&self, relative_pointer: &str) -> Self {
+        Self {
+            op_id: self.op_id,
+            pointer: format!("{}{relative_pointer}", self.pointer),
+        }
+    }
+
+    /// Create a violation of `rule` at a pointer relative to the operation object.
+    fn violation(&self, relative_pointer: &str, rule: &'static str) -> SpecViolation {
+        SpecViolation {
+            op_id: self.op_id.to_owned(),
+            pointer: format!("{}{relative_pointer}", self.pointer),
+            rule,
         }
     }
 }
 
+fn escape_json_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// A rule of the spec conversion that an operation does not follow.
+///
+/// Depending on strictness, the operation is either skipped or the conversion fails.
+#[derive(Debug)]
+pub(crate) struct SpecViolation {
+    op_id: String,
+    /// JSON pointer to the offending part of the spec.
+    pointer: String,
+    rule: &'static str,
+}
+
+impl fmt::Display for SpecViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            op_id,
+            pointer,
+            rule,
+        } = self;
+        write!(f, "operation `{op_id}` at `{pointer}`: {rule}")
+    }
+}
+
+impl std::error::Error for SpecViolation {}
+
 #[derive(Deserialize, Serialize)]
 struct HeaderParam {
+    /// Name of the header on the wire.
     name: String,
+    /// Name to use in the generated code instead of `name`, from a rename rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code_name: Option<String>,
     required: bool,
+    #[serde(default)]
+    extensions: Extensions,
 }
 
 #[derive(Deserialize, Serialize)]
 struct QueryParam {
+    /// Name of the query parameter on the wire.
     name: String,
+    /// Name to use in the generated code instead of `name`, from a rename rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     required: bool,
     #[serde(serialize_with = "serialize_field_type")]
     r#type: FieldType,
+    #[serde(default)]
+    extensions: Extensions,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use serde_json::json;
+
+    use crate::{
+        IncludeMode,
+        api::{Api, ParamRules},
+    };
+
+    #[test]
+    fn empty_operation_security() {
+        let response = json!({ "204": { "description": "no content" } });
+        let spec = json!({
+            "openapi": "3.1.0",
+            "info": { "title": "test", "version": "1" },
+            "security": [{ "HTTPBearer": [] }],
+            "paths": {
+                "/api/v1/app": {
+                    "get": { "operationId": "v1.app.list", "responses": response },
+                    "post": {
+                        "operationId": "v1.app.create",
+                        "security": [],
+                        "responses": response,
+                    },
+                },
+            },
+        });
+        let api = Api::new(
+            spec,
+            false,
+            IncludeMode::Public,
+            &BTreeSet::new(),
+            &BTreeSet::new(),
+            &ParamRules::default(),
+            true,
+        )
+        .unwrap();
+
+        let operations = &api.resources["app"].operations;
+        let security = |name: &str| {
+            let op = operations.iter().find(|op| op.name == name).unwrap();
+            op.security.clone()
+        };
+        assert_eq!(
+            security("list"),
+            [[("HTTPBearer".to_owned(), vec![])].into()]
+        );
+        assert!(security("create").is_empty());
+    }
 }
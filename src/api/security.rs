@@ -0,0 +1,276 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use aide::openapi::{self, ReferenceOr};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::JsonValue;
+
+/// Authentication schemes that operations can require, by name.
+///
+/// Intermediate representation of `components.securitySchemes` from the spec.
+pub type SecuritySchemes = BTreeMap<String, SecurityScheme>;
+
+/// A set of security schemes that must all be satisfied to authorize a request.
+///
+/// Maps the scheme name to the scopes required from it. An empty requirement allows anonymous
+/// access.
+pub type SecurityRequirement = BTreeMap<String, Vec<String>>;
+
+pub(crate) fn from_openapi(
+    schemes: IndexMap<String, ReferenceOr<openapi::SecurityScheme>>,
+) -> SecuritySchemes {
+    schemes
+        .into_iter()
+        .filter_map(|(name, scheme)| {
+            let ReferenceOr::Item(scheme) = scheme else {
+                tracing::warn!(name, "$ref security schemes are not currently supported");
+                return None;
+            };
+            let scheme = SecurityScheme::from_openapi(name.clone(), scheme);
+            Some((name, scheme))
+        })
+        .collect()
+}
+
+/// The spec's top-level security requirements, which apply to operations that don't declare
+/// their own.
+pub(crate) struct DefaultSecurity {
+    requirements: Vec<SecurityRequirement>,
+    /// Path and method of the operations that declare `security`, including `security: []` for
+    /// anonymous access.
+    declared: BTreeSet<(String, String)>,
+}
+
+impl DefaultSecurity {
+    /// aide deserializes a missing `security` and `security: []` to the same empty list, so the
+    /// operations declaring it are looked up in the JSON `spec`.
+    pub(crate) fn new(spec: &JsonValue, requirements: Vec<openapi::SecurityRequirement>) -> Self {
+        let declared = spec["paths"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, op)| op.get("security").is_some())
+                    .map(move |(method, _)| (path.clone(), method.clone()))
+            })
+            .collect();
+
+        Self {
+            requirements: requirements_from_openapi(requirements),
+            declared,
+        }
+    }
+
+    /// The requirements of the operation at `path` and `method`, given the ones it declares.
+    pub(crate) fn apply(
+        &self,
+        path: &str,
+        method: &str,
+        requirements: Vec<SecurityRequirement>,
+    ) -> Vec<SecurityRequirement> {
+        if self
+            .declared
+            .contains(&(path.to_owned(), method.to_owned()))
+        {
+            requirements
+        } else {
+            self.requirements.clone()
+        }
+    }
+}
+
+pub(crate) fn requirements_from_openapi(
+    requirements: Vec<openapi::SecurityRequirement>,
+) -> Vec<SecurityRequirement> {
+    requirements
+        .into_iter()
+        .map(|req| req.into_iter().collect())
+        .collect()
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct SecurityScheme {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(flatten)]
+    pub data: SecuritySchemeData,
+}
+
+impl SecurityScheme {
+    fn from_openapi(name: String, scheme: openapi::SecurityScheme) -> Self {
+        let (description, data) = match scheme {
+            openapi::SecurityScheme::ApiKey {
+                location,
+                name: param_name,
+                description,
+                ..
+            } => {
+                let location = match location {
+                    openapi::ApiKeyLocation::Query => ApiKeyLocation::Query,
+                    openapi::ApiKeyLocation::Header => ApiKeyLocation::Header,
+                    openapi::ApiKeyLocation::Cookie => ApiKeyLocation::Cookie,
+                };
+                let data = SecuritySchemeData::ApiKey {
+                    location,
+                    param_name,
+                };
+                (description, data)
+            }
+            openapi::SecurityScheme::Http {
+                scheme,
+                bearer_format,
+                description,
+                ..
+            } => {
+                let data = SecuritySchemeData::Http {
+                    scheme: scheme.to_lowercase(),
+                    bearer_format,
+                };
+                (description, data)
+            }
+            openapi::SecurityScheme::OAuth2 {
+                flows, description, ..
+            } => {
+                let flows = [
+                    flows.implicit,
+                    flows.password,
+                    flows.client_credentials,
+                    flows.authorization_code,
+                ]
+                .into_iter()
+                .flatten()
+                .map(OAuth2Flow::from_openapi)
+                .collect();
+                (description, SecuritySchemeData::OAuth2 { flows })
+            }
+            openapi::SecurityScheme::OpenIdConnect {
+                open_id_connect_url,
+                description,
+                ..
+            } => {
+                let data = SecuritySchemeData::OpenIdConnect {
+                    url: open_id_connect_url,
+                };
+                (description, data)
+            }
+            openapi::SecurityScheme::MutualTls { description, .. } => {
+                (description, SecuritySchemeData::MutualTls)
+            }
+        };
+
+        Self {
+            name,
+            description,
+            data,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SecuritySchemeData {
+    /// An API key sent as a header, query parameter or cookie.
+    ApiKey {
+        location: ApiKeyLocation,
+        /// Name of the header, query parameter or cookie.
+        param_name: String,
+    },
+    /// HTTP authentication using the `Authorization` header.
+    Http {
+        /// Lowercase name of the authorization scheme, e.g. `bearer` or `basic`.
+        scheme: String,
+        /// Hint for how bearer tokens are formatted.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bearer_format: Option<String>,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        flows: Vec<OAuth2Flow>,
+    },
+    OpenIdConnect {
+        /// URL of the OpenID Connect discovery document.
+        url: String,
+    },
+    MutualTls,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyLocation {
+    Query,
+    Header,
+    Cookie,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct OAuth2Flow {
+    /// `implicit`, `password`, `client_credentials` or `authorization_code`.
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_url: Option<String>,
+    /// Available scopes, with their descriptions.
+    scopes: BTreeMap<String, String>,
+}
+
+impl OAuth2Flow {
+    fn from_openapi(flow: openapi::OAuth2Flow) -> Self {
+        let (kind, authorization_url, token_url, refresh_url, scopes) = match flow {
+            openapi::OAuth2Flow::Implicit {
+                authorization_url,
+                refresh_url,
+                scopes,
+            } => (
+                "implicit",
+                Some(authorization_url),
+                None,
+                refresh_url,
+                scopes,
+            ),
+            openapi::OAuth2Flow::Password {
+                refresh_url,
+                token_url,
+                scopes,
+            } => ("password", None, Some(token_url), refresh_url, scopes),
+            openapi::OAuth2Flow::ClientCredentials {
+                refresh_url,
+                token_url,
+                scopes,
+            } => (
+                "client_credentials",
+                None,
+                Some(token_url),
+                refresh_url,
+                scopes,
+            ),
+            openapi::OAuth2Flow::AuthorizationCode {
+                authorization_url,
+                token_url,
+                refresh_url,
+                scopes,
+            } => (
+                "authorization_code",
+                Some(authorization_url),
+                Some(token_url),
+                refresh_url,
+                scopes,
+            ),
+        };
+
+        Self {
+            kind: kind.to_owned(),
+            authorization_url,
+            token_url,
+            refresh_url,
+            scopes: scopes.into_iter().collect(),
+        }
+    }
+}
@@ -1,9 +1,13 @@
+use std::collections::BTreeSet;
+
+use aide::openapi;
 use anyhow::{Context as _, bail, ensure};
+use indexmap::IndexMap;
 
 use crate::{
     JsonValue,
     api::{
-        get_schema_name,
+        all_of, get_schema_name,
         types::{EnumVariantType, Field, SimpleVariant, StructEnumRepr, TypeData},
     },
     utils::get_properties,
@@ -28,71 +32,363 @@ impl SameString {
 }
 
 impl TypeData {
-    pub(super) fn inline_struct_enum(one_of: &JsonValue, fields: &[Field]) -> anyhow::Result<Self> {
-        let one_of = one_of.as_array().context("oneOf must be an array")?;
+    /// Create a struct enum from a `oneOf` of `$ref`s with a `discriminator` object.
+    ///
+    /// Variants are named after the keys of the discriminator's `mapping`, or after the
+    /// referenced schema if no mapping key points to it.
+    ///
+    /// Returns `None` if the schema has no `oneOf` or some of its variants are inline schemas.
+    pub(super) fn from_discriminator(
+        schema: &JsonValue,
+        discriminator: &JsonValue,
+    ) -> anyhow::Result<Option<Self>> {
+        let (keyword, members) = match (schema.get("oneOf"), schema.get("anyOf")) {
+            (Some(one_of), None) => ("oneOf", one_of),
+            (None, Some(any_of)) => ("anyOf", any_of),
+            (Some(_), Some(_)) => bail!("unsupported: both oneOf and anyOf"),
+            (None, None) => return Ok(None),
+        };
+        let members = members
+            .as_array()
+            .with_context(|| format!("{keyword} must be an array"))?;
+        if !members.iter().all(|m| m.get("$ref").is_some()) {
+            return Ok(None);
+        }
 
-        let mut discriminator_field = SameString(None);
-        let mut content_field = SameString(None);
-        let mut variants = vec![];
+        let discriminator_field = discriminator["propertyName"]
+            .as_str()
+            .context("discriminator must have a propertyName")?;
+        let mapping = match discriminator.get("mapping") {
+            Some(mapping) => mapping
+                .as_object()
+                .context("discriminator mapping must be an object")?
+                .iter()
+                .map(|(name, target)| {
+                    let target = target
+                        .as_str()
+                        .context("discriminator mapping values must be strings")?;
+                    // mapping values can be either a $ref or a bare schema name
+                    let schema_name = if target.starts_with('#') {
+                        get_schema_name(target)
+                            .with_context(|| format!("unsupported mapping target `{target}`"))?
+                    } else {
+                        target.to_owned()
+                    };
+                    Ok((name.as_str(), schema_name))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            None => vec![],
+        };
 
-        let mut process_one_of = |variant: &JsonValue| {
-            let (variant_discriminator_name, discriminator) =
-                get_discriminator(variant).context("get struct-enum discriminator")?;
-            discriminator_field.update(variant_discriminator_name)?;
+        let mut mapped = BTreeSet::new();
+        let mut variants = vec![];
+        for (idx, member) in members.iter().enumerate() {
+            let schema_name = get_schema_name(member["$ref"].as_str()).with_context(|| {
+                format!("{keyword}[{idx}]: discriminated union variants must be $refs")
+            })?;
+            let mut names: Vec<_> = mapping
+                .iter()
+                .filter(|(_, target)| *target == schema_name)
+                .map(|(name, _)| *name)
+                .collect();
+            if names.is_empty() {
+                names.push(&schema_name);
+            }
 
-            let properties = get_properties(variant).context("get struct-enum properties")?;
-            let len = properties.len();
-            ensure!(
-                (1..=2).contains(&len),
-                "Found struct enum variant with {len} properties, expected 1 or 2"
-            );
-            if properties.len() == 1 {
+            for name in names {
+                mapped.insert(name.to_owned());
                 variants.push(SimpleVariant {
-                    name: discriminator,
+                    name: name.to_owned(),
                     content: EnumVariantType::Ref {
-                        schema_ref: None,
+                        schema_ref: Some(schema_name.clone()),
                         inner: None,
+                        recursive: false,
                     },
                 });
-            } else {
-                let (variant_content_field, content) =
-                    get_content(variant).context("get struct-enum content")?;
-                content_field.update(variant_content_field)?;
+            }
+        }
+        if let Some((name, target)) = mapping.iter().find(|(name, _)| !mapped.contains(*name)) {
+            bail!("discriminator mapping `{name}` points to `{target}`, which is not in {keyword}");
+        }
 
-                variants.push(SimpleVariant {
-                    name: discriminator,
-                    content,
-                });
+        Ok(Some(Self::StructEnum {
+            fields: shared_fields(schema, discriminator_field)?,
+            repr: StructEnumRepr::InternallyTagged {
+                discriminator_field: discriminator_field.to_owned(),
+                variants,
+            },
+        }))
+    }
+
+    /// Create a struct enum from a `oneOf` of `$ref`s whose schemas all have a discriminator
+    /// property, without a `discriminator` object.
+    ///
+    /// Returns `None` if the schema isn't such a `oneOf`.
+    pub(super) fn from_tagged_refs(
+        schema: &JsonValue,
+        schemas: &IndexMap<String, openapi::SchemaObject>,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(one_of) = schema.get("oneOf").and_then(JsonValue::as_array) else {
+            return Ok(None);
+        };
+
+        let mut discriminator_field = SameString(None);
+        let mut variants = vec![];
+        for member in one_of {
+            let Some(schema_name) = get_schema_name(member["$ref"].as_str()) else {
+                return Ok(None);
+            };
+            let Some(variant_schema) = schemas.get(&schema_name) else {
+                return Ok(None);
+            };
+            let variant_schema = all_of::flatten(variant_schema.json_schema.as_value(), schemas)
+                .with_context(|| format!("flatten oneOf variant `{schema_name}`"))?
+                .schema;
+            let Ok((variant_discriminator_name, discriminator)) =
+                get_discriminator(&variant_schema)
+            else {
+                return Ok(None);
+            };
+            if discriminator_field
+                .update(variant_discriminator_name)
+                .is_err()
+            {
+                return Ok(None);
             }
 
-            Ok(())
+            variants.push(SimpleVariant {
+                name: discriminator,
+                content: EnumVariantType::Ref {
+                    schema_ref: Some(schema_name),
+                    inner: None,
+                    recursive: false,
+                },
+            });
+        }
+
+        let Some(discriminator_field) = discriminator_field.inner() else {
+            return Ok(None);
         };
+        Ok(Some(Self::StructEnum {
+            fields: shared_fields(schema, &discriminator_field)?,
+            repr: StructEnumRepr::InternallyTagged {
+                discriminator_field,
+                variants,
+            },
+        }))
+    }
+
+    pub(super) fn inline_struct_enum(one_of: &JsonValue, fields: &[Field]) -> anyhow::Result<Self> {
+        let one_of = one_of.as_array().context("oneOf must be an array")?;
+
+        if is_externally_tagged(one_of) {
+            ensure!(
+                fields.is_empty(),
+                "unsupported: externally tagged enum with shared fields"
+            );
+            let variants = one_of
+                .iter()
+                .enumerate()
+                .map(|(idx, variant)| {
+                    let (name, content) = get_content(variant)
+                        .with_context(|| format!("oneOf[{idx}]: get struct-enum content"))?;
+                    Ok(SimpleVariant { name, content })
+                })
+                .collect::<anyhow::Result<_>>()?;
 
-        for (idx, s) in one_of.iter().enumerate() {
-            process_one_of(s).with_context(|| format!("oneOf[{idx}]"))?;
+            return Ok(Self::StructEnum {
+                fields: vec![],
+                repr: StructEnumRepr::ExternallyTagged { variants },
+            });
         }
 
+        let mut discriminator_field = SameString(None);
+        let mut discriminators = vec![];
+        for (idx, variant) in one_of.iter().enumerate() {
+            let (variant_discriminator_name, discriminator) = get_discriminator(variant)
+                .with_context(|| format!("oneOf[{idx}]: get struct-enum discriminator"))?;
+            discriminator_field
+                .update(variant_discriminator_name)
+                .with_context(|| format!("oneOf[{idx}]: mismatched discriminator field"))?;
+            discriminators.push(discriminator);
+        }
+        let discriminator_field = discriminator_field
+            .inner()
+            .context("failed to find discriminator field")?;
+
+        if let Some(content_field) = get_content_field(one_of, &discriminator_field)? {
+            let variants = one_of
+                .iter()
+                .zip(discriminators)
+                .enumerate()
+                .map(|(idx, (variant, name))| {
+                    let content = if get_properties(variant)?.len() == 1 {
+                        EnumVariantType::Ref {
+                            schema_ref: None,
+                            inner: None,
+                            recursive: false,
+                        }
+                    } else {
+                        get_content(variant)
+                            .with_context(|| format!("oneOf[{idx}]: get struct-enum content"))?
+                            .1
+                    };
+                    Ok(SimpleVariant { name, content })
+                })
+                .collect::<anyhow::Result<_>>()?;
+
+            return Ok(Self::StructEnum {
+                fields: fields.to_vec(),
+                repr: StructEnumRepr::AdjacentlyTagged {
+                    discriminator_field,
+                    content_field,
+                    variants,
+                },
+            });
+        }
+
+        let variants = one_of
+            .iter()
+            .zip(discriminators)
+            .enumerate()
+            .map(|(idx, (variant, name))| {
+                let fields = get_variant_fields(variant, &discriminator_field)
+                    .with_context(|| format!("oneOf[{idx}]: get struct-enum variant fields"))?;
+                let content = if fields.is_empty() {
+                    EnumVariantType::Ref {
+                        schema_ref: None,
+                        inner: None,
+                        recursive: false,
+                    }
+                } else {
+                    EnumVariantType::Struct { fields }
+                };
+                Ok(SimpleVariant { name, content })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
         Ok(Self::StructEnum {
-            discriminator_field: discriminator_field
-                .inner()
-                .context("failed to find discriminator field")?,
             fields: fields.to_vec(),
-            repr: StructEnumRepr::AdjacentlyTagged {
-                content_field: content_field
-                    .inner()
-                    .context("failed to find content field")?,
+            repr: StructEnumRepr::InternallyTagged {
+                discriminator_field,
                 variants,
             },
         })
     }
 }
 
+/// Properties next to the `oneOf` of `schema`, which are shared by all variants.
+fn shared_fields(schema: &JsonValue, discriminator_field: &str) -> anyhow::Result<Vec<Field>> {
+    let mut base = schema.clone();
+    let base_obj = base.as_object_mut().context("schema must be an object")?;
+    for key in ["oneOf", "anyOf", "discriminator"] {
+        base_obj.remove(key);
+    }
+    let TypeData::Struct {
+        fields,
+        additional_properties: None,
+        ..
+    } = TypeData::from_object_schema(&base)?
+    else {
+        bail!("Expected obj to be a struct without additionalProperties");
+    };
+
+    Ok(fields
+        .into_iter()
+        .filter(|f| f.name != discriminator_field)
+        .collect())
+}
+
+/// Find the field that contains the variant-specific fields, if the variants are adjacently
+/// tagged.
+///
+/// That's the case if no variant has more than one property next to the discriminator, and
+/// those properties have the same name and contain an object.
+fn get_content_field(
+    one_of: &[JsonValue],
+    discriminator_field: &str,
+) -> anyhow::Result<Option<String>> {
+    let mut content_field = SameString(None);
+    for variant in one_of {
+        let properties = get_properties(variant)?;
+        let mut others = properties
+            .iter()
+            .filter(|(name, _)| *name != discriminator_field);
+        match (others.next(), others.next()) {
+            (None, _) => {}
+            (Some((name, schema)), None)
+                if schema["type"] == "object" || schema.get("$ref").is_some() =>
+            {
+                if content_field.update(name.clone()).is_err() {
+                    return Ok(None);
+                }
+            }
+            _ => return Ok(None),
+        }
+    }
+
+    Ok(content_field.inner())
+}
+
+/// The properties of an internally tagged variant, except for the discriminator.
+fn get_variant_fields(
+    variant: &JsonValue,
+    discriminator_field: &str,
+) -> anyhow::Result<Vec<Field>> {
+    let mut variant = variant.clone();
+    if let Some(properties) = variant
+        .get_mut("properties")
+        .and_then(JsonValue::as_object_mut)
+    {
+        properties.retain(|name, _| name != discriminator_field);
+    }
+    let TypeData::Struct {
+        fields,
+        additional_properties: None,
+        ..
+    } = TypeData::from_object_schema(&variant)?
+    else {
+        bail!("Expected obj to be a struct without additionalProperties");
+    };
+
+    Ok(fields)
+}
+
+/// Whether every variant is an object with a single required property named after the variant,
+/// which contains the variant's object.
+pub(super) fn is_externally_tagged(one_of: &[JsonValue]) -> bool {
+    let mut names = BTreeSet::new();
+    one_of.iter().all(|variant| {
+        let Ok(properties) = get_properties(variant) else {
+            return false;
+        };
+        let Some((name, content)) = properties.iter().next() else {
+            return false;
+        };
+        let required = variant["required"]
+            .as_array()
+            .is_some_and(|r| r.iter().any(|n| n == name));
+
+        variant["type"] == "object"
+            && properties.len() == 1
+            && required
+            && (content["type"] == "object" || content.get("$ref").is_some())
+            && get_discriminator(variant).is_err()
+            && names.insert(name.as_str())
+    }) && !one_of.is_empty()
+}
+
 fn get_content(variant: &JsonValue) -> anyhow::Result<(String, EnumVariantType)> {
     for (prop_name, prop_schema) in get_properties(variant)? {
         if prop_schema["type"] == "object" {
             let ty = TypeData::from_object_schema(prop_schema)?;
-            let TypeData::Struct { fields } = ty else {
-                bail!("Expected obj to be a struct");
+            let TypeData::Struct {
+                fields,
+                additional_properties: None,
+                ..
+            } = ty
+            else {
+                bail!("Expected obj to be a struct without additionalProperties");
             };
 
             return Ok((prop_name.to_owned(), EnumVariantType::Struct { fields }));
@@ -102,6 +398,7 @@ fn get_content(variant: &JsonValue) -> anyhow::Result<(String, EnumVariantType)>
                 EnumVariantType::Ref {
                     schema_ref: Some(get_schema_name(reference).unwrap()),
                     inner: None,
+                    recursive: false,
                 },
             ));
         }
@@ -110,7 +407,7 @@ fn get_content(variant: &JsonValue) -> anyhow::Result<(String, EnumVariantType)>
     bail!("Failed to find content on struct enum")
 }
 
-fn get_discriminator(obj: &JsonValue) -> anyhow::Result<(String, String)> {
+pub(super) fn get_discriminator(obj: &JsonValue) -> anyhow::Result<(String, String)> {
     let mut discriminator_field_name = None;
     let mut discriminator = None;
 
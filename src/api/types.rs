@@ -9,11 +9,18 @@ use anyhow::{Context as _, bail, ensure};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-use crate::{JsonValue, utils::get_properties};
+use crate::{
+    JsonValue,
+    utils::{get_extensions, get_properties},
+};
 
 use super::{
-    get_schema_name,
+    Deprecation, Extensions, all_of, get_schema_name, inline_schemas,
+    read_write::Direction,
+    recursion,
     resources::{self, Resources},
+    struct_enum,
+    webhooks::Webhook,
 };
 
 /// Named types referenced by API operations.
@@ -24,20 +31,26 @@ pub type Types = BTreeMap<String, Type>;
 pub(crate) fn from_referenced_components(
     res: &Resources,
     mut schemas: IndexMap<String, openapi::SchemaObject>,
-    webhooks: &[String],
+    webhooks: &[Webhook],
 ) -> Types {
+    inline_schemas::hoist(&mut schemas);
+
     let components: Vec<&str> = resources::referenced_components(res)
-        .chain(webhooks.iter().map(String::as_str))
+        .chain(webhooks.iter().map(|w| w.payload_schema_name.as_str()))
         .collect();
 
     let mut types = BTreeMap::new();
+    let mut visited = BTreeSet::new();
     let mut add_type = |schema_name: &str, extra_components: &mut BTreeSet<_>| {
-        let Some(s) = schemas.swap_remove(schema_name) else {
+        if !visited.insert(schema_name.to_owned()) {
+            return;
+        }
+        let Some(s) = schemas.get(schema_name) else {
             tracing::warn!(schema_name, "schema not found");
             return;
         };
 
-        match Type::from_schema(schema_name.to_owned(), s.json_schema.as_value()) {
+        match Type::from_schema(schema_name.to_owned(), s.json_schema.as_value(), &schemas) {
             Ok(ty) => {
                 extra_components.extend(
                     ty.referenced_components()
@@ -58,6 +71,7 @@ pub(crate) fn from_referenced_components(
         add_type(&c, &mut extra_components);
     }
 
+    recursion::mark_recursive_refs(&mut types);
     types
 }
 
@@ -67,92 +81,133 @@ pub struct Type {
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     deprecated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecation: Option<Deprecation>,
+    #[serde(default)]
+    extensions: Extensions,
     #[serde(flatten)]
     pub data: TypeData,
 }
 
 impl Type {
-    pub(crate) fn from_schema(name: String, schema: &JsonValue) -> anyhow::Result<Self> {
+    pub(crate) fn from_schema(
+        name: String,
+        schema: &JsonValue,
+        schemas: &IndexMap<String, openapi::SchemaObject>,
+    ) -> anyhow::Result<Self> {
         ensure!(schema.is_object(), "schema must be an object");
 
-        let instance_type = match schema.get("type") {
-            Some(JsonValue::String(ty)) => Some(ty.as_str()),
-            Some(ty) => bail!("invalid / unsupported type `{ty:?}`"),
-            None => {
-                let mut result = None;
-
-                for variant in schema
-                    .get("oneOf")
-                    .iter()
-                    .filter_map(|v| v.as_array())
-                    .flatten()
-                {
-                    if let Some(ty) = &variant.get("type") {
-                        match ty {
-                            JsonValue::String(ty) => {
-                                if let Some(res_ty) = result {
-                                    ensure!(
-                                        res_ty == ty,
-                                        "unsupported: oneOf schemas with different types"
-                                    );
-                                } else {
-                                    result = Some(ty.as_str());
+        let all_of::FlattenedSchema { schema, parents } = all_of::flatten(schema, schemas)?;
+        let schema = &schema;
+
+        let data = if let Some(discriminator) = schema.get("discriminator")
+            && let Some(struct_enum) = TypeData::from_discriminator(schema, discriminator)?
+        {
+            struct_enum
+        } else if let Some(struct_enum) = TypeData::from_tagged_refs(schema, schemas)? {
+            struct_enum
+        } else if let Some(union) = TypeData::from_untagged_union(schema)? {
+            union
+        } else {
+            let instance_type = match schema.get("type") {
+                Some(JsonValue::String(ty)) => Some(ty.as_str()),
+                Some(ty) => bail!("invalid / unsupported type `{ty:?}`"),
+                None => {
+                    let mut result = None;
+
+                    for variant in schema
+                        .get("oneOf")
+                        .iter()
+                        .filter_map(|v| v.as_array())
+                        .flatten()
+                    {
+                        if let Some(ty) = &variant.get("type") {
+                            match ty {
+                                JsonValue::String(ty) => {
+                                    if let Some(res_ty) = result {
+                                        ensure!(
+                                            res_ty == ty,
+                                            "unsupported: oneOf schemas with different types"
+                                        );
+                                    } else {
+                                        result = Some(ty.as_str());
+                                    }
                                 }
+                                _ => bail!("invalid / unsupported type `{ty:?}` in oneOf"),
                             }
-                            _ => bail!("invalid / unsupported type `{ty:?}` in oneOf"),
                         }
                     }
-                }
 
-                result
-            }
-        };
-
-        let data = match instance_type {
-            Some("object") => TypeData::from_object_schema(schema)?,
-            Some("integer") => {
-                let enum_varnames = schema
-                    .get("x-enum-varnames")
-                    .context("unsupported: integer type without enum varnames")?
-                    .as_array()
-                    .context("unsupported: integer type enum varnames should be a list")?;
-                let values = schema
-                    .get("enum")
-                    .context("unsupported: integer type without enum values")?
-                    .as_array()
-                    .context("enum must be an array")?;
-                if enum_varnames.len() != values.len() {
-                    bail!(
-                        "enum varnames length ({}) does not match values length ({})",
-                        enum_varnames.len(),
-                        values.len()
-                    );
+                    result
                 }
-                TypeData::from_integer_enum(values, enum_varnames)?
-            }
-            Some("string") => {
-                let values = schema
-                    .get("enum")
-                    .context("unsupported: string type without enum values")?
-                    .as_array()
-                    .context("enum must be an array")?;
-                TypeData::from_string_enum(values)?
+            };
+
+            match instance_type {
+                Some("object") if parents.is_empty() && is_map(schema) => TypeData::Alias {
+                    target: FieldType::from_schema(schema)?,
+                },
+                Some("object") => match TypeData::from_object_schema(schema)? {
+                    TypeData::Struct {
+                        fields,
+                        additional_properties,
+                        ..
+                    } => TypeData::Struct {
+                        fields,
+                        parents,
+                        additional_properties,
+                    },
+                    data => {
+                        ensure!(parents.is_empty(), "unsupported: allOf with oneOf");
+                        data
+                    }
+                },
+                Some("integer") if schema.get("enum").is_some() => {
+                    let values = schema["enum"].as_array().context("enum must be an array")?;
+                    TypeData::from_integer_enum(schema, values)?
+                }
+                Some("string") if schema.get("enum").is_some() => {
+                    let values = schema["enum"].as_array().context("enum must be an array")?;
+                    TypeData::from_string_enum(schema, values)?
+                }
+                Some("boolean" | "integer" | "number" | "string" | "array") => TypeData::Alias {
+                    target: FieldType::from_schema(schema)?,
+                },
+                Some(ty) => bail!("unsupported type {ty:?}"),
+                None if schema.get("$ref").is_some() => TypeData::Alias {
+                    target: FieldType::from_schema(schema)?,
+                },
+                None => bail!("unsupported: schema without a type"),
             }
-            Some(ty) => bail!("unsupported type {ty:?}"),
-            None => bail!("unsupported: schema without a type"),
         };
 
+        let deprecated = schema["deprecated"].as_bool().unwrap_or(false);
+        let extensions = get_extensions(schema);
         Ok(Self {
             name,
             description: schema["description"].as_str().map(ToOwned::to_owned),
-            deprecated: schema["deprecated"].as_bool().unwrap_or(false),
+            deprecated,
+            deprecation: Deprecation::from_extensions(deprecated, &extensions),
+            extensions,
             data,
         })
     }
 
     pub(crate) fn referenced_components(&self) -> BTreeSet<&str> {
         match &self.data {
-            TypeData::Struct { fields } => fields_referenced_schemas(fields),
+            TypeData::Struct {
+                fields,
+                parents,
+                additional_properties,
+            } => {
+                let mut res = fields_referenced_schemas(fields);
+                res.extend(parents.iter().map(String::as_str));
+                res.extend(
+                    additional_properties
+                        .as_ref()
+                        .and_then(FieldType::referenced_schema),
+                );
+                res
+            }
             TypeData::StringEnum { .. } => BTreeSet::new(),
             TypeData::IntegerEnum { .. } => BTreeSet::new(),
             TypeData::StructEnum { repr, fields, .. } => {
@@ -160,10 +215,175 @@ impl Type {
                 res.append(&mut fields_referenced_schemas(fields));
                 res
             }
+            TypeData::Union { members, .. } => members
+                .iter()
+                .filter_map(|m| m.r#type.referenced_schema())
+                .collect(),
+            TypeData::Alias { target } => target.referenced_schema().into_iter().collect(),
         }
     }
 }
 
+impl Type {
+    /// Whether any field of the type is read-only or write-only.
+    pub(crate) fn has_read_write_only_fields(&self) -> bool {
+        self.field_lists()
+            .into_iter()
+            .flatten()
+            .any(|f| f.read_only || f.write_only)
+    }
+
+    /// A copy of the type under a new name, with only the fields sent in `direction`.
+    pub(crate) fn for_direction(&self, name: String, direction: Direction) -> Self {
+        let mut ty = Self {
+            name,
+            ..self.clone()
+        };
+        for fields in ty.field_lists_mut() {
+            fields.retain(|f| match direction {
+                Direction::Input => !f.read_only,
+                Direction::Output => !f.write_only,
+            });
+        }
+        ty
+    }
+
+    /// Call `f` with the name of every type this one refers to.
+    pub(crate) fn for_each_schema_name_mut(&mut self, f: &mut impl FnMut(&mut String)) {
+        match &mut self.data {
+            TypeData::Struct {
+                fields,
+                parents,
+                additional_properties,
+            } => {
+                for field in fields {
+                    field.r#type.for_each_schema_name_mut(f);
+                }
+                parents.iter_mut().for_each(&mut *f);
+                if let Some(ty) = additional_properties {
+                    ty.for_each_schema_name_mut(f);
+                }
+            }
+            TypeData::StringEnum { .. } | TypeData::IntegerEnum { .. } => {}
+            TypeData::StructEnum { repr, fields, .. } => {
+                for field in fields {
+                    field.r#type.for_each_schema_name_mut(f);
+                }
+                for variant in repr.variants_mut() {
+                    match &mut variant.content {
+                        EnumVariantType::Struct { fields } => {
+                            for field in fields {
+                                field.r#type.for_each_schema_name_mut(f);
+                            }
+                        }
+                        EnumVariantType::Ref { schema_ref, .. } => {
+                            if let Some(name) = schema_ref {
+                                f(name);
+                            }
+                        }
+                    }
+                }
+            }
+            TypeData::Union { members, .. } => {
+                for member in members {
+                    member.r#type.for_each_schema_name_mut(f);
+                }
+            }
+            TypeData::Alias { target } => target.for_each_schema_name_mut(f),
+        }
+    }
+
+    /// Call `f` with the name of every type this one contains by value, rather than through a
+    /// list, set or map, along with the `recursive` flag of the field, variant or union member
+    /// that refers to it.
+    pub(crate) fn for_each_direct_ref_mut(&mut self, f: &mut impl FnMut(&str, &mut bool)) {
+        if let TypeData::StructEnum { repr, .. } = &mut self.data {
+            for variant in repr.variants_mut() {
+                if let EnumVariantType::Ref {
+                    schema_ref: Some(name),
+                    recursive,
+                    ..
+                } = &mut variant.content
+                {
+                    f(name, recursive);
+                }
+            }
+        }
+        if let TypeData::Union { members, .. } = &mut self.data {
+            for member in members {
+                if let FieldType::SchemaRef { name, .. } = &member.r#type {
+                    f(name, &mut member.recursive);
+                }
+            }
+        }
+        // Aliases can't add indirection, but recursive references can go through them.
+        if let TypeData::Alias {
+            target: FieldType::SchemaRef { name, .. },
+        } = &self.data
+        {
+            f(name, &mut false);
+        }
+        for fields in self.field_lists_mut() {
+            for field in fields {
+                if let FieldType::SchemaRef { name, .. } = &field.r#type {
+                    f(name, &mut field.recursive);
+                }
+            }
+        }
+    }
+
+    /// The fields of the type, including those of struct enum variants.
+    fn field_lists(&self) -> Vec<&Vec<Field>> {
+        match &self.data {
+            TypeData::Struct { fields, .. } => vec![fields],
+            TypeData::StructEnum { repr, fields, .. } => {
+                let mut res = vec![fields];
+                res.extend(repr.variants().iter().filter_map(|v| match &v.content {
+                    EnumVariantType::Struct { fields } => Some(fields),
+                    EnumVariantType::Ref { .. } => None,
+                }));
+                res
+            }
+            TypeData::StringEnum { .. }
+            | TypeData::IntegerEnum { .. }
+            | TypeData::Union { .. }
+            | TypeData::Alias { .. } => vec![],
+        }
+    }
+
+    fn field_lists_mut(&mut self) -> Vec<&mut Vec<Field>> {
+        match &mut self.data {
+            TypeData::Struct { fields, .. } => vec![fields],
+            TypeData::StructEnum { repr, fields, .. } => {
+                let mut res = vec![fields];
+                res.extend(
+                    repr.variants_mut()
+                        .iter_mut()
+                        .filter_map(|v| match &mut v.content {
+                            EnumVariantType::Struct { fields } => Some(fields),
+                            EnumVariantType::Ref { .. } => None,
+                        }),
+                );
+                res
+            }
+            TypeData::StringEnum { .. }
+            | TypeData::IntegerEnum { .. }
+            | TypeData::Union { .. }
+            | TypeData::Alias { .. } => vec![],
+        }
+    }
+}
+
+/// Whether `schema` is an object with typed `additionalProperties` and nothing else, i.e. a map.
+fn is_map(schema: &JsonValue) -> bool {
+    schema["additionalProperties"].is_object()
+        && schema["properties"]
+            .as_object()
+            .is_none_or(|properties| properties.is_empty())
+        && schema.get("oneOf").is_none()
+        && schema.get("anyOf").is_none()
+}
+
 fn fields_referenced_schemas(fields: &[Field]) -> BTreeSet<&str> {
     fields
         .iter()
@@ -176,17 +396,25 @@ fn fields_referenced_schemas(fields: &[Field]) -> BTreeSet<&str> {
 pub enum TypeData {
     Struct {
         fields: Vec<Field>,
+        /// Types this one was composed from using `allOf`, including their fields.
+        #[serde(default)]
+        parents: Vec<String>,
+        /// Type of the values of properties other than `fields`, if those are allowed.
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize_optional_field_type"
+        )]
+        additional_properties: Option<FieldType>,
     },
     StringEnum {
-        values: Vec<String>,
+        #[serde(alias = "values")]
+        variants: Vec<EnumVariant<String>>,
     },
     IntegerEnum {
-        variants: Vec<(String, i64)>,
+        variants: Vec<EnumVariant<i64>>,
     },
     StructEnum {
-        /// Name of the field that identifies the variant.
-        discriminator_field: String,
-
         /// JSON representation of the enum variants.
         #[serde(flatten)]
         repr: StructEnumRepr,
@@ -194,14 +422,29 @@ pub enum TypeData {
         /// Variant-independent fields.
         fields: Vec<Field>,
     },
+    /// A value matching one of several types, without a field that tells them apart.
+    Union {
+        /// Whether the value matches exactly one of the members (`oneOf`), rather than at least
+        /// one of them (`anyOf`).
+        exclusive: bool,
+        members: Vec<UnionMember>,
+    },
+    /// Another name for a field type, e.g. a string with a format, a list or a map.
+    Alias {
+        #[serde(serialize_with = "serialize_field_type")]
+        target: FieldType,
+    },
 }
 
 impl TypeData {
     pub(super) fn from_object_schema(obj: &JsonValue) -> anyhow::Result<Self> {
-        ensure!(
-            obj.get("additionalProperties").is_none(),
-            "additionalProperties not yet supported"
-        );
+        let additional_properties = match obj.get("additionalProperties") {
+            None | Some(JsonValue::Bool(false)) => None,
+            Some(JsonValue::Bool(true)) => bail!("unsupported: untyped additionalProperties"),
+            Some(schema) => {
+                Some(FieldType::from_schema(schema).context("unsupported additionalProperties")?)
+            }
+        };
         ensure!(
             obj.get("maxProperties").is_none(),
             "unsupported: maxProperties"
@@ -249,62 +492,244 @@ impl TypeData {
         );
 
         if let Some(one_of) = obj.get("oneOf") {
+            ensure!(
+                additional_properties.is_none(),
+                "unsupported: additionalProperties with oneOf"
+            );
             return Self::inline_struct_enum(one_of, &fields);
         }
 
-        Ok(Self::Struct { fields })
+        Ok(Self::Struct {
+            fields,
+            parents: vec![],
+            additional_properties,
+        })
+    }
+
+    /// Create a union from a `oneOf` or `anyOf` schema without a discriminator.
+    ///
+    /// Returns `None` if the schema isn't such a union, including `oneOf`s whose variants are
+    /// inline objects with a discriminator property, which are turned into struct enums.
+    fn from_untagged_union(schema: &JsonValue) -> anyhow::Result<Option<Self>> {
+        if schema.get("type").is_some() || schema.get("discriminator").is_some() {
+            return Ok(None);
+        }
+        let (keyword, exclusive, members) = match (schema.get("oneOf"), schema.get("anyOf")) {
+            (Some(_), Some(_)) => bail!("unsupported: both oneOf and anyOf"),
+            (Some(one_of), None) => ("oneOf", true, one_of),
+            (None, Some(any_of)) => ("anyOf", false, any_of),
+            (None, None) => return Ok(None),
+        };
+        let members = members
+            .as_array()
+            .with_context(|| format!("{keyword} must be an array"))?;
+        if members
+            .iter()
+            .all(|m| m["type"] == "object" && struct_enum::get_discriminator(m).is_ok())
+            || struct_enum::is_externally_tagged(members)
+        {
+            return Ok(None);
+        }
+
+        let members = members
+            .iter()
+            .enumerate()
+            .map(|(idx, schema)| {
+                Ok(UnionMember {
+                    r#type: FieldType::from_schema(schema)
+                        .with_context(|| format!("unsupported {keyword}[{idx}]"))?,
+                    description: schema["description"].as_str().map(ToOwned::to_owned),
+                    recursive: false,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Some(Self::Union { exclusive, members }))
     }
 
-    fn from_string_enum(values: &[JsonValue]) -> anyhow::Result<TypeData> {
+    fn from_string_enum(schema: &JsonValue, values: &[JsonValue]) -> anyhow::Result<TypeData> {
+        let values = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                Ok(v.as_str()
+                    .with_context(|| format!("enum value {} is not a string", i + 1))?
+                    .to_owned())
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
         Ok(Self::StringEnum {
-            values: values
-                .iter()
-                .enumerate()
-                .map(|(i, v)| {
-                    Ok(v.as_str()
-                        .with_context(|| format!("enum value {} is not a string", i + 1))?
-                        .to_owned())
-                })
-                .collect::<anyhow::Result<_>>()?,
+            variants: EnumVariant::from_schema(schema, values, |value| value.clone())?,
         })
     }
 
-    fn from_integer_enum(
-        values: &[JsonValue],
-        enum_varnames: &[JsonValue],
-    ) -> anyhow::Result<TypeData> {
+    fn from_integer_enum(schema: &JsonValue, values: &[JsonValue]) -> anyhow::Result<TypeData> {
+        let values = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| match v {
+                JsonValue::Number(s) => s
+                    .as_i64()
+                    .with_context(|| format!("enum value {s} is not an integer")),
+                _ => bail!("enum value {} is not a number", i + 1),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
         Ok(Self::IntegerEnum {
-            variants: values
-                .iter()
-                .enumerate()
-                .map(|(i, v)| match v {
-                    JsonValue::Number(s) => {
-                        let num = s
-                            .as_i64()
-                            .with_context(|| format!("enum value {s} is not an integer"))?;
-                        Ok((
-                            enum_varnames[i]
-                                .as_str()
-                                .context(format!(
-                                    "enum varname {} is not a string",
-                                    enum_varnames[i]
-                                ))?
-                                .to_string(),
-                            num,
-                        ))
-                    }
-                    _ => bail!("enum value {} is not a number", i + 1),
-                })
-                .collect::<anyhow::Result<_>>()?,
+            variants: EnumVariant::from_schema(schema, values, |&value| {
+                if value < 0 {
+                    format!("ValueMinus{}", value.unsigned_abs())
+                } else {
+                    format!("Value{value}")
+                }
+            })?,
         })
     }
 }
 
+/// A variant of a [`TypeData::StringEnum`] or [`TypeData::IntegerEnum`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(
+    from = "EnumVariantRepr<V>",
+    bound(deserialize = "V: Deserialize<'de> + ToString")
+)]
+pub struct EnumVariant<V> {
+    /// Name of the variant, from `x-enum-varnames` or `x-enumNames`.
+    ///
+    /// Defaults to the value for string enums, and to `Value1` and such for integer enums.
+    pub name: String,
+    pub value: V,
+    /// From `x-enum-descriptions`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// From `x-enum-deprecated`.
+    deprecated: bool,
+}
+
+impl<V> EnumVariant<V> {
+    /// Attach the names, descriptions and deprecation flags from the enum's extensions to
+    /// `values`.
+    fn from_schema(
+        schema: &JsonValue,
+        values: Vec<V>,
+        default_name: impl Fn(&V) -> String,
+    ) -> anyhow::Result<Vec<Self>> {
+        let names = match enum_extension(schema, "x-enum-varnames", values.len())? {
+            Some(names) => Some(names),
+            None => enum_extension(schema, "x-enumNames", values.len())?,
+        };
+        let descriptions = enum_extension(schema, "x-enum-descriptions", values.len())?;
+        let deprecated = enum_extension(schema, "x-enum-deprecated", values.len())?;
+
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let name = match names {
+                    Some(names) => names[i]
+                        .as_str()
+                        .with_context(|| format!("enum varname {} is not a string", names[i]))?
+                        .to_owned(),
+                    None => default_name(&value),
+                };
+                let description = match descriptions.map(|d| &d[i]) {
+                    None | Some(JsonValue::Null) => None,
+                    Some(JsonValue::String(d)) => Some(d.clone()),
+                    Some(d) => bail!("enum description {d} is not a string"),
+                };
+                let deprecated = match deprecated.map(|d| &d[i]) {
+                    None => false,
+                    Some(JsonValue::Bool(d)) => *d,
+                    Some(d) => bail!("enum deprecation flag {d} is not a boolean"),
+                };
+
+                Ok(Self {
+                    name,
+                    value,
+                    description,
+                    deprecated,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Get an extension with one entry per enum value.
+fn enum_extension<'a>(
+    schema: &'a JsonValue,
+    key: &str,
+    len: usize,
+) -> anyhow::Result<Option<&'a [JsonValue]>> {
+    let Some(ext) = schema.get(key) else {
+        return Ok(None);
+    };
+    let ext = ext
+        .as_array()
+        .with_context(|| format!("{key} should be a list"))?;
+    ensure!(
+        ext.len() == len,
+        "{key} length ({}) does not match values length ({len})",
+        ext.len()
+    );
+    Ok(Some(ext))
+}
+
+/// Accepts the older representations of enum variants, too.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EnumVariantRepr<V> {
+    Full {
+        name: String,
+        value: V,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        deprecated: bool,
+    },
+    /// Integer enum variants used to be `(name, value)` tuples.
+    Tuple(String, V),
+    /// String enum variants used to be bare values.
+    Value(V),
+}
+
+impl<V: ToString> From<EnumVariantRepr<V>> for EnumVariant<V> {
+    fn from(repr: EnumVariantRepr<V>) -> Self {
+        match repr {
+            EnumVariantRepr::Full {
+                name,
+                value,
+                description,
+                deprecated,
+            } => Self {
+                name,
+                value,
+                description,
+                deprecated,
+            },
+            EnumVariantRepr::Tuple(name, value) => Self {
+                name,
+                value,
+                description: None,
+                deprecated: false,
+            },
+            EnumVariantRepr::Value(value) => Self {
+                name: value.to_string(),
+                value,
+                description: None,
+                deprecated: false,
+            },
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "repr", rename_all = "snake_case")]
 pub enum StructEnumRepr {
     // add more variants here to support other enum representations
     AdjacentlyTagged {
+        /// Name of the field that identifies the variant.
+        discriminator_field: String,
+
         /// Name of the field that contains the variant-specific fields.
         content_field: String,
 
@@ -314,18 +739,55 @@ pub enum StructEnumRepr {
         /// identify the variant.
         variants: Vec<SimpleVariant>,
     },
+    /// The discriminator field is part of each variant's own fields.
+    InternallyTagged {
+        /// Name of the field that identifies the variant.
+        discriminator_field: String,
+
+        /// Enum variants.
+        ///
+        /// Every variant has a discriminator value that's stored in the discriminator field,
+        /// next to the variant-specific fields.
+        variants: Vec<SimpleVariant>,
+    },
+    /// The variant is identified by the name of the object's only field, which contains the
+    /// variant-specific fields.
+    ExternallyTagged {
+        /// Enum variants.
+        ///
+        /// Every variant's name is the name of the field that contains it.
+        variants: Vec<SimpleVariant>,
+    },
 }
 
 impl StructEnumRepr {
+    fn variants(&self) -> &[SimpleVariant] {
+        match self {
+            StructEnumRepr::AdjacentlyTagged { variants, .. }
+            | StructEnumRepr::InternallyTagged { variants, .. }
+            | StructEnumRepr::ExternallyTagged { variants } => variants,
+        }
+    }
+
+    fn variants_mut(&mut self) -> &mut [SimpleVariant] {
+        match self {
+            StructEnumRepr::AdjacentlyTagged { variants, .. }
+            | StructEnumRepr::InternallyTagged { variants, .. }
+            | StructEnumRepr::ExternallyTagged { variants } => variants,
+        }
+    }
+
     fn referenced_components(&self) -> BTreeSet<&str> {
         match self {
-            StructEnumRepr::AdjacentlyTagged { variants, .. } => variants
+            StructEnumRepr::AdjacentlyTagged { variants, .. }
+            | StructEnumRepr::InternallyTagged { variants, .. }
+            | StructEnumRepr::ExternallyTagged { variants } => variants
                 .iter()
-                .filter_map(|v| match &v.content {
-                    EnumVariantType::Struct { fields } => {
-                        fields.iter().find_map(|f| f.r#type.referenced_schema())
+                .flat_map(|v| match &v.content {
+                    EnumVariantType::Struct { fields } => fields_referenced_schemas(fields),
+                    EnumVariantType::Ref { schema_ref, .. } => {
+                        schema_ref.as_deref().into_iter().collect()
                     }
-                    EnumVariantType::Ref { schema_ref, .. } => schema_ref.as_deref(),
                 })
                 .collect(),
         }
@@ -334,7 +796,7 @@ impl StructEnumRepr {
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Field {
-    name: String,
+    pub(crate) name: String,
     #[serde(serialize_with = "serialize_field_type")]
     pub r#type: FieldType,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -343,11 +805,29 @@ pub struct Field {
     description: Option<String>,
     required: bool,
     nullable: bool,
+    /// Whether the field is only sent by the server, like a server-assigned ID.
+    #[serde(default)]
+    read_only: bool,
+    /// Whether the field is only sent by the client, like a secret.
+    #[serde(default)]
+    write_only: bool,
     deprecated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecation: Option<Deprecation>,
     #[serde(default)]
     positional: bool,
+    /// Whether the field's type contains the type of the field by value, directly or through
+    /// other types.
+    ///
+    /// Languages that store such values inline need indirection for the field, e.g. a `Box`.
+    #[serde(default)]
+    recursive: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     example: Option<JsonValue>,
+    #[serde(default)]
+    extensions: Extensions,
+    #[serde(default, skip_serializing_if = "Constraints::is_empty")]
+    constraints: Constraints,
 }
 
 impl Field {
@@ -362,19 +842,131 @@ impl Field {
             .get("nullable")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
+        let deprecated = schema["deprecated"].as_bool().unwrap_or(false);
+        let read_only = schema["readOnly"].as_bool().unwrap_or(false);
+        let write_only = schema["writeOnly"].as_bool().unwrap_or(false);
+        ensure!(
+            !(read_only && write_only),
+            "field can't be both readOnly and writeOnly"
+        );
+        let extensions = get_extensions(schema);
+        let r#type = FieldType::from_schema(schema)?;
+        let constraints = Constraints::from_schema(schema, &r#type)?;
 
         Ok(Self {
             name,
-            r#type: FieldType::from_schema(schema)?,
+            r#type,
             default: schema.get("default").cloned(),
             description: schema["description"].as_str().map(ToOwned::to_owned),
             required,
             nullable,
+            read_only,
+            write_only,
             positional,
-            deprecated: schema["deprecated"].as_bool().unwrap_or(false),
+            recursive: false,
+            deprecated,
+            deprecation: Deprecation::from_extensions(deprecated, &extensions),
             example,
+            extensions,
+            constraints,
+        })
+    }
+}
+
+/// Validation constraints of a field, from the JSON schema keywords of the same name.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Constraints {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_length: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_length: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    minimum: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    maximum: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exclusive_minimum: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exclusive_maximum: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    multiple_of: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_items: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_items: Option<u64>,
+    /// `format` of a string field without a field type of its own, like `email` or `uuid`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+}
+
+impl Constraints {
+    fn from_schema(schema: &JsonValue, r#type: &FieldType) -> anyhow::Result<Self> {
+        let count = |key| -> anyhow::Result<_> {
+            schema
+                .get(key)
+                .map(|v| {
+                    v.as_u64()
+                        .with_context(|| format!("{key} must be a non-negative integer"))
+                })
+                .transpose()
+        };
+        let number = |key| -> anyhow::Result<_> {
+            schema
+                .get(key)
+                .map(|v| {
+                    v.as_number()
+                        .cloned()
+                        .with_context(|| format!("{key} must be a number"))
+                })
+                .transpose()
+        };
+
+        let mut minimum = number("minimum")?;
+        let mut maximum = number("maximum")?;
+        // OpenAPI 3.0 uses booleans that make `minimum` / `maximum` exclusive,
+        // OpenAPI 3.1 uses the exclusive bounds themselves.
+        let exclusive_bound = |key, inclusive: &mut Option<_>| -> anyhow::Result<_> {
+            match schema.get(key) {
+                None | Some(JsonValue::Bool(false)) => Ok(None),
+                Some(JsonValue::Bool(true)) => {
+                    let bound = inclusive.take();
+                    if bound.is_none() {
+                        tracing::warn!("ignoring {key} without a bound");
+                    }
+                    Ok(bound)
+                }
+                Some(JsonValue::Number(n)) => Ok(Some(n.clone())),
+                Some(_) => bail!("{key} must be a number or a boolean"),
+            }
+        };
+        let exclusive_minimum = exclusive_bound("exclusiveMinimum", &mut minimum)?;
+        let exclusive_maximum = exclusive_bound("exclusiveMaximum", &mut maximum)?;
+
+        let format = match r#type {
+            FieldType::String => schema["format"].as_str().map(ToOwned::to_owned),
+            _ => None,
+        };
+
+        Ok(Self {
+            min_length: count("minLength")?,
+            max_length: count("maxLength")?,
+            pattern: schema["pattern"].as_str().map(ToOwned::to_owned),
+            minimum,
+            maximum,
+            exclusive_minimum,
+            exclusive_maximum,
+            multiple_of: number("multipleOf")?,
+            min_items: count("minItems")?,
+            max_items: count("maxItems")?,
+            format,
         })
     }
+
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -387,10 +979,24 @@ pub enum EnumVariantType {
         #[serde(skip_serializing_if = "Option::is_none")]
         schema_ref: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        inner: Option<Type>,
+        inner: Option<Box<Type>>,
+        /// Whether the referenced type contains this one by value, see [`Field::recursive`].
+        #[serde(default)]
+        recursive: bool,
     },
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UnionMember {
+    #[serde(serialize_with = "serialize_field_type")]
+    pub r#type: FieldType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Whether the member's type contains the union by value, see [`Field::recursive`].
+    #[serde(default)]
+    recursive: bool,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct SimpleVariant {
     /// Discriminator value that identifies this variant.
@@ -406,7 +1012,10 @@ pub struct SimpleVariant {
 #[serde(tag = "id")]
 pub enum FieldType {
     Bool,
+    Float32,
     Float64,
+    /// An arbitrary-precision decimal number, encoded as a string.
+    Decimal,
     Int8,
     UInt8,
     Int16,
@@ -417,7 +1026,18 @@ pub enum FieldType {
     UInt64,
     String,
     DateTime,
+    /// A calendar date, without a time.
+    Date,
+    /// A time of day, without a date.
+    Time,
+    /// An ISO 8601 duration.
+    Duration,
     Uri,
+    Uuid,
+    /// Binary data, base64-encoded into a string.
+    Base64Bytes,
+    /// Raw binary data, such as a file upload.
+    Binary,
     /// A JSON object with arbitrary field values.
     JsonObject,
     /// A regular old list.
@@ -438,7 +1058,7 @@ pub enum FieldType {
     SchemaRef {
         name: String,
         #[serde(skip_serializing_if = "Option::is_none")]
-        inner: Option<Type>,
+        inner: Option<Box<Type>>,
     },
 
     UnixTimestampMs,
@@ -484,7 +1104,8 @@ impl FieldType {
                         f => bail!("unsupported integer format: `{f:?}`"),
                     },
                     "number" => match schema["format"].as_str() {
-                        Some("double") => Self::Float64,
+                        None | Some("double") => Self::Float64,
+                        Some("float") => Self::Float32,
                         f => bail!("unsupported number format: `{f:?}`"),
                     },
                     "string" => {
@@ -510,9 +1131,18 @@ impl FieldType {
                         }
 
                         match schema["format"].as_str() {
-                            None | Some("color") | Some("email") | Some("uuid") => Self::String,
+                            None | Some("color" | "email" | "hostname" | "ipv4" | "ipv6") => {
+                                Self::String
+                            }
                             Some("date-time") => Self::DateTime,
+                            Some("date") => Self::Date,
+                            Some("time") => Self::Time,
+                            Some("duration") => Self::Duration,
                             Some("uri") => Self::Uri,
+                            Some("uuid") => Self::Uuid,
+                            Some("byte") => Self::Base64Bytes,
+                            Some("binary") => Self::Binary,
+                            Some("decimal") => Self::Decimal,
                             Some(f) => bail!("unsupported string format: `{f:?}`"),
                         }
                     }
@@ -585,7 +1215,9 @@ impl FieldType {
     fn to_csharp_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool => "bool".into(),
+            Self::Float32 => "float".into(),
             Self::Float64 => "double".into(),
+            Self::Decimal => "decimal".into(),
             Self::Int8 => "sbyte".into(),
             Self::UInt8 => "byte".into(),
             Self::Int16 => "short".into(),
@@ -596,7 +1228,13 @@ impl FieldType {
             Self::UInt64 => "ulong".into(),
             Self::String => "string".into(),
             Self::DateTime => "DateTime".into(),
+            Self::Date => "DateOnly".into(),
+            Self::Time => "TimeOnly".into(),
+            // TimeSpan doesn't use the ISO 8601 format
+            Self::Duration => "string".into(),
             Self::Uri => "string".into(),
+            Self::Uuid => "Guid".into(),
+            Self::Base64Bytes | Self::Binary => "byte[]".into(),
             Self::JsonObject => "Object".into(),
             Self::Map { value_ty } => {
                 format!("Dictionary<string, {}>", value_ty.to_csharp_typename()).into()
@@ -604,7 +1242,7 @@ impl FieldType {
             Self::List { inner } | Self::Set { inner } => {
                 format!("List<{}>", inner.to_csharp_typename()).into()
             }
-            Self::SchemaRef { name, .. } => filter_schema_ref(name, "Object"),
+            Self::SchemaRef { name, .. } => name.clone().into(),
             Self::StringConst { .. } => "string".into(),
             Self::UnixTimestampMs | Self::DurationMs => "ulong".into(),
         }
@@ -613,6 +1251,7 @@ impl FieldType {
     fn to_go_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool => "bool".into(),
+            Self::Float32 => "float32".into(),
             Self::Float64 => "float64".into(),
             Self::Int8 => "int8".into(),
             Self::UInt8 => "uint8".into(),
@@ -622,14 +1261,22 @@ impl FieldType {
             Self::UInt32 => "uint32".into(),
             Self::Int64 => "int64".into(),
             Self::UInt64 => "uint64".into(),
-            Self::Uri | Self::String => "string".into(),
+            Self::Uri
+            | Self::String
+            | Self::Date
+            | Self::Time
+            | Self::Duration
+            | Self::Uuid
+            | Self::Decimal => "string".into(),
             Self::DateTime => "time.Time".into(),
+            // encoding/json base64-encodes byte slices
+            Self::Base64Bytes | Self::Binary => "[]byte".into(),
             Self::JsonObject => "map[string]any".into(),
             Self::Map { value_ty } => format!("map[string]{}", value_ty.to_go_typename()).into(),
             Self::List { inner } | Self::Set { inner } => {
                 format!("[]{}", inner.to_go_typename()).into()
             }
-            Self::SchemaRef { name, .. } => filter_schema_ref(name, "map[string]any"),
+            Self::SchemaRef { name, .. } => name.clone().into(),
             Self::StringConst { .. } => "string".into(),
             Self::UnixTimestampMs | Self::DurationMs => "uint64".into(),
         }
@@ -638,6 +1285,7 @@ impl FieldType {
     fn to_kotlin_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool => "Boolean".into(),
+            Self::Float32 => "Float".into(),
             Self::Float64 => "Double".into(),
             Self::Int8 => "Byte".into(),
             Self::UInt8 => "UByte".into(),
@@ -647,15 +1295,21 @@ impl FieldType {
             Self::UInt32 => "UInt".into(),
             Self::Int64 => "Long".into(),
             Self::UInt64 => "ULong".into(),
-            Self::Uri | Self::String => "String".into(),
+            Self::Uri | Self::String | Self::Uuid | Self::Decimal | Self::Base64Bytes => {
+                "String".into()
+            }
             Self::DateTime => "Instant".into(),
+            Self::Date => "kotlinx.datetime.LocalDate".into(),
+            Self::Time => "kotlinx.datetime.LocalTime".into(),
+            Self::Duration => "kotlin.time.Duration".into(),
+            Self::Binary => "ByteArray".into(),
             Self::Map { value_ty } => {
                 format!("Map<String, {}>", value_ty.to_kotlin_typename()).into()
             }
             Self::JsonObject => "Map<String, Any>".into(),
             Self::List { inner } => format!("List<{}>", inner.to_kotlin_typename()).into(),
             Self::Set { inner } => format!("Set<{}>", inner.to_kotlin_typename()).into(),
-            Self::SchemaRef { name, .. } => filter_schema_ref(name, "Map<String, Any>"),
+            Self::SchemaRef { name, .. } => name.clone().into(),
             Self::StringConst { .. } => "String".into(),
             Self::UnixTimestampMs | Self::DurationMs => "ULong".into(),
         }
@@ -672,9 +1326,18 @@ impl FieldType {
             | Self::UInt32
             | Self::Int64
             | Self::UInt64
+            | Self::Float32
             | Self::Float64 => "number".into(),
-            Self::String | Self::Uri => "string".into(),
+            Self::String
+            | Self::Uri
+            | Self::Date
+            | Self::Time
+            | Self::Duration
+            | Self::Uuid
+            | Self::Decimal
+            | Self::Base64Bytes => "string".into(),
             Self::DateTime => "Date".into(),
+            Self::Binary => "Blob".into(),
             Self::JsonObject => "any".into(),
             Self::List { inner } if matches!(**inner, Self::Int8) => "Int8Array".into(),
             Self::List { inner } if matches!(**inner, Self::UInt8) => "Uint8Array".into(),
@@ -688,7 +1351,7 @@ impl FieldType {
             Self::Map { value_ty } => {
                 format!("{{ [key: string]: {} }}", value_ty.to_js_typename()).into()
             }
-            Self::SchemaRef { name, .. } => filter_schema_ref(name, "any"),
+            Self::SchemaRef { name, .. } => name.clone().into(),
             Self::StringConst { value } => format!("\"{value}\"").into(),
             Self::UnixTimestampMs | Self::DurationMs => "number".into(),
         }
@@ -697,6 +1360,7 @@ impl FieldType {
     fn to_rust_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool => "bool".into(),
+            Self::Float32 => "f32".into(),
             Self::Float64 => "f64".into(),
             Self::Int8 => "i8".into(),
             Self::UInt8 => "u8".into(),
@@ -708,7 +1372,13 @@ impl FieldType {
             Self::UInt64 | Self::UnixTimestampMs | Self::DurationMs => "u64".into(),
             // FIXME: Do we want a separate type for Uri?
             Self::Uri | Self::String => "String".into(),
+            // Only use crates the generated code depends on already.
+            Self::Uuid | Self::Decimal | Self::Base64Bytes => "String".into(),
             Self::DateTime => "jiff::Timestamp".into(),
+            Self::Date => "jiff::civil::Date".into(),
+            Self::Time => "jiff::civil::Time".into(),
+            Self::Duration => "jiff::SignedDuration".into(),
+            Self::Binary => "Vec<u8>".into(),
             Self::JsonObject => "serde_json::Value".into(),
             Self::List { inner } => format!("Vec<{}>", inner.to_rust_typename()).into(),
             Self::Set { inner } => {
@@ -719,19 +1389,25 @@ impl FieldType {
                 value_ty.to_rust_typename(),
             )
             .into(),
-            Self::SchemaRef { name, .. } => filter_schema_ref(name, "serde_json::Value"),
+            Self::SchemaRef { name, .. } => name.clone().into(),
             Self::StringConst { .. } => "String".into(),
         }
     }
 
-    pub(crate) fn referenced_schema(&self) -> Option<&str> {
+    /// Call `f` with the name of the type this one refers to, if any.
+    pub(crate) fn for_each_schema_name_mut(&mut self, f: &mut impl FnMut(&mut String)) {
         match self {
-            Self::SchemaRef { name, .. } => {
-                // TODO(10055): the `BackgroundTaskFinishedEvent2` struct has a field with type of `Data`
-                // this corresponds to a `#[serde(untagged)]` enum `svix_server::v1::endpoints::background_tasks::Data`
-                // we should change this server side, but for now I am changing it here
-                if name == "Data" { None } else { Some(name) }
+            Self::SchemaRef { name, .. } => f(name),
+            Self::List { inner: ty } | Self::Set { inner: ty } | Self::Map { value_ty: ty } => {
+                Arc::make_mut(ty).for_each_schema_name_mut(f);
             }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn referenced_schema(&self) -> Option<&str> {
+        match self {
+            Self::SchemaRef { name, .. } => Some(name),
             Self::List { inner: ty } | Self::Set { inner: ty } | Self::Map { value_ty: ty } => {
                 ty.referenced_schema()
             }
@@ -742,7 +1418,8 @@ impl FieldType {
     fn to_python_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool => "bool".into(),
-            Self::Float64 => "float".into(),
+            Self::Float32 | Self::Float64 => "float".into(),
+            Self::Decimal => "Decimal".into(),
             Self::Int8
             | Self::UInt8
             | Self::Int16
@@ -751,9 +1428,14 @@ impl FieldType {
             | Self::UInt32
             | Self::Int64
             | Self::UInt64 => "int".into(),
-            Self::String => "str".into(),
+            Self::String | Self::Base64Bytes => "str".into(),
             Self::DateTime => "datetime".into(),
-            Self::SchemaRef { name, .. } => filter_schema_ref(name, "t.Dict[str, t.Any]"),
+            Self::Date => "date".into(),
+            Self::Time => "time".into(),
+            Self::Duration => "timedelta".into(),
+            Self::Uuid => "UUID".into(),
+            Self::Binary => "bytes".into(),
+            Self::SchemaRef { name, .. } => name.clone().into(),
             Self::Uri => "str".into(),
             Self::JsonObject => "t.Dict[str, t.Any]".into(),
             Self::List { inner } if matches!(**inner, Self::UInt8) => "bytes".into(),
@@ -771,14 +1453,21 @@ impl FieldType {
     fn to_java_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool => "Boolean".into(),
+            Self::Float32 => "Float".into(),
             Self::Float64 => "Double".into(),
+            Self::Decimal => "java.math.BigDecimal".into(),
             Self::Int8 | Self::UInt8 => "Byte".into(),
             Self::Int16 | Self::UInt16 => "Short".into(),
             Self::Int32 | Self::UInt32 => "Integer".into(),
             Self::UInt64 | Self::Int64 => "Long".into(),
             Self::String => "String".into(),
             Self::DateTime => "OffsetDateTime".into(),
+            Self::Date => "java.time.LocalDate".into(),
+            Self::Time => "java.time.LocalTime".into(),
+            Self::Duration => "java.time.Duration".into(),
             Self::Uri => "URI".into(),
+            Self::Uuid => "java.util.UUID".into(),
+            Self::Base64Bytes | Self::Binary => "byte[]".into(),
             Self::JsonObject => "Object".into(),
             Self::List { inner } => format!("List<{}>", inner.to_java_typename()).into(),
             Self::Set { inner: field_type } => {
@@ -787,7 +1476,7 @@ impl FieldType {
             Self::Map { value_ty } => {
                 format!("Map<String, {}>", value_ty.to_java_typename()).into()
             }
-            Self::SchemaRef { name, .. } => filter_schema_ref(name, "Object"),
+            Self::SchemaRef { name, .. } => name.clone().into(),
             // backwards compat
             Self::StringConst { .. } => "TypeEnum".into(),
             Self::UnixTimestampMs | Self::DurationMs => "Long".into(),
@@ -808,7 +1497,9 @@ impl FieldType {
     fn to_phpdoc_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool
+            | Self::Float32
             | Self::Float64
+            | Self::Decimal
             | Self::Int8
             | Self::UInt8
             | Self::Int16
@@ -819,7 +1510,13 @@ impl FieldType {
             | Self::UInt64
             | Self::String
             | Self::DateTime
+            | Self::Date
+            | Self::Time
+            | Self::Duration
             | Self::Uri
+            | Self::Uuid
+            | Self::Base64Bytes
+            | Self::Binary
             | Self::JsonObject
             | Self::StringConst { .. }
             | Self::SchemaRef { .. }
@@ -837,7 +1534,7 @@ impl FieldType {
     fn to_php_typename(&self) -> Cow<'_, str> {
         match self {
             Self::Bool => "bool".into(),
-            Self::Float64 => "double".into(),
+            Self::Float32 | Self::Float64 => "double".into(),
             Self::Int8
             | Self::UInt8
             | Self::UInt16
@@ -846,7 +1543,16 @@ impl FieldType {
             | Self::Int32
             | Self::UInt32
             | Self::Int64 => "int".into(),
-            Self::Uri | Self::StringConst { .. } | Self::String => "string".into(),
+            Self::Uri
+            | Self::StringConst { .. }
+            | Self::String
+            | Self::Date
+            | Self::Time
+            | Self::Duration
+            | Self::Uuid
+            | Self::Decimal
+            | Self::Base64Bytes
+            | Self::Binary => "string".into(),
             Self::DateTime => r#"\DateTimeImmutable"#.into(),
 
             Self::JsonObject | Self::List { .. } | Self::Set { .. } | Self::Map { .. } => {
@@ -975,10 +1681,18 @@ impl minijinja::value::Object for FieldType {
                     | FieldType::UInt64 => true,
 
                     FieldType::Bool
+                    | FieldType::Float32
                     | FieldType::Float64
+                    | FieldType::Decimal
                     | FieldType::String
                     | FieldType::DateTime
+                    | FieldType::Date
+                    | FieldType::Time
+                    | FieldType::Duration
                     | FieldType::Uri
+                    | FieldType::Uuid
+                    | FieldType::Base64Bytes
+                    | FieldType::Binary
                     | FieldType::JsonObject
                     | FieldType::List { .. }
                     | FieldType::Set { .. }
@@ -1074,13 +1788,28 @@ where
     }
 }
 
-fn filter_schema_ref<'a>(name: &'a String, json_obj_typename: &'a str) -> Cow<'a, str> {
-    // TODO(10055): the `BackgroundTaskFinishedEvent2` struct has a field with type of `Data`
-    // this corresponds to a `#[serde(untagged)]` enum `svix_server::v1::endpoints::background_tasks::Data`
-    // we should change this server side, but for now I am changing it here
-    if name == "Data" {
-        json_obj_typename.into()
-    } else {
-        name.clone().into()
+/// Like `serialize_field_type`, for optional fields.
+fn serialize_optional_field_type<S>(
+    field_ty: &Option<FieldType>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match field_ty {
+        Some(field_ty) => serializer.serialize_some(&SerializeFieldType(field_ty)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Wrapper to serialize a `FieldType` with `serialize_field_type`.
+struct SerializeFieldType<'a>(&'a FieldType);
+
+impl Serialize for SerializeFieldType<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_field_type(self.0, serializer)
     }
 }
@@ -0,0 +1,106 @@
+use aide::openapi::{self, ReferenceOr};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::JsonValue;
+
+use super::get_schema_name;
+
+/// Collect the webhooks from `webhooks` and the older `x-webhooks` extension of the spec.
+///
+/// Only the first declaration of an event name is kept.
+pub(crate) fn from_openapi(
+    webhooks: IndexMap<String, ReferenceOr<openapi::PathItem>>,
+    x_webhooks: Option<&JsonValue>,
+) -> Vec<Webhook> {
+    let mut result: Vec<Webhook> = Vec::new();
+
+    let x_webhooks = x_webhooks
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, item)| match openapi::PathItem::deserialize(item) {
+            Ok(item) => Some((name.clone(), item)),
+            Err(e) => {
+                tracing::warn!(name, "unsupported x-webhooks entry: {e}");
+                None
+            }
+        });
+    let webhooks = webhooks.into_iter().filter_map(|(name, item)| match item {
+        ReferenceOr::Item(item) => Some((name, item)),
+        ReferenceOr::Reference { .. } => {
+            tracing::warn!(name, "$ref webhooks are not currently supported");
+            None
+        }
+    });
+
+    for (name, item) in x_webhooks.chain(webhooks) {
+        for (method, op) in item {
+            let _guard = tracing::info_span!("webhook_from_openapi", name, method).entered();
+            let Some(webhook) = Webhook::from_openapi(&name, method, op) else {
+                continue;
+            };
+            // webhooks are identified by their name in the generated code, e.g. in file names
+            match result.iter().find(|w| w.name == webhook.name) {
+                Some(existing) if *existing == webhook => {}
+                Some(existing) => tracing::warn!(
+                    declared_method = existing.method,
+                    "skipping webhook, an event with the same name was already declared"
+                ),
+                None => result.push(webhook),
+            }
+        }
+    }
+
+    result
+}
+
+/// An event that the API sends to subscribers.
+#[derive(Deserialize, Serialize, PartialEq)]
+pub struct Webhook {
+    /// Name of the event, e.g. `endpoint.created`.
+    pub(crate) name: String,
+    /// The HTTP method the event is delivered with.
+    method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    deprecated: bool,
+    /// Name of the payload type.
+    pub(crate) payload_schema_name: String,
+}
+
+impl Webhook {
+    fn from_openapi(name: &str, method: &str, op: openapi::Operation) -> Option<Self> {
+        let body = match op.request_body? {
+            ReferenceOr::Item(body) => body,
+            ReferenceOr::Reference { .. } => {
+                tracing::warn!("$ref request bodies are not currently supported");
+                return None;
+            }
+        };
+        let Some(schema) = body
+            .content
+            .get("application/json")
+            .and_then(|json_body| json_body.schema.as_ref())
+        else {
+            tracing::warn!("webhook should have JSON body");
+            return None;
+        };
+        let reference = schema.json_schema.get("$ref").and_then(JsonValue::as_str);
+        let Some(payload_schema_name) = get_schema_name(reference) else {
+            tracing::warn!("unexpected non-$ref webhook body schema");
+            return None;
+        };
+
+        Some(Self {
+            name: name.to_owned(),
+            method: method.to_owned(),
+            summary: op.summary,
+            description: op.description,
+            deprecated: op.deprecated,
+            payload_schema_name,
+        })
+    }
+}
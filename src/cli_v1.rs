@@ -8,7 +8,6 @@ use std::{
     },
 };
 
-use aide::openapi::OpenApi;
 use anyhow::{Context as _, bail};
 use camino::Utf8PathBuf;
 use clap::{Parser, Subcommand};
@@ -20,7 +19,10 @@ use tracing_subscriber::{
     util::SubscriberInitExt as _,
 };
 
-use crate::{api::Api, generator::generate};
+use crate::{
+    api::{Api, ParamRules},
+    generator::generate,
+};
 
 #[derive(Parser)]
 struct CliArgs {
@@ -28,7 +30,7 @@ struct CliArgs {
     #[arg(global = true, long, value_enum, default_value_t = IncludeMode::Public)]
     include_mode: IncludeMode,
 
-    /// Include webhooks in component models.
+    /// Include webhooks and their payload types.
     #[arg(global = true, long)]
     include_webhooks: bool,
 
@@ -42,6 +44,20 @@ struct CliArgs {
     #[arg(global = true, long = "include-op-id")]
     specified_operations: Vec<String>,
 
+    /// Path to a JSON file with rules for dropping, renaming or including parameters.
+    ///
+    /// The svix-lib templates expect the rules in `templates/svix-param-rules.json`.
+    #[arg(global = true, long)]
+    param_rules: Option<Utf8PathBuf>,
+
+    /// Fail on operations that can't be converted, instead of skipping them.
+    #[arg(global = true, long)]
+    strict: bool,
+
+    /// Generate separate input and output types for types with readOnly or writeOnly fields.
+    #[arg(global = true, long)]
+    split_read_write: bool,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -101,13 +117,35 @@ pub fn run_cli_v1_main() -> anyhow::Result<()> {
 
     let excluded_operations = BTreeSet::from_iter(args.excluded_operations);
     let specified_operations = BTreeSet::from_iter(args.specified_operations);
+    let param_rules = match &args.param_rules {
+        Some(path) => {
+            let rules = fs::read_to_string(path)
+                .with_context(|| format!("failed to read parameter rules from `{path}`"))?;
+            ParamRules::from_json(&rules)
+                .with_context(|| format!("failed to parse parameter rules from `{path}`"))?
+        }
+        None => {
+            if let Command::Generate { template, .. } = &args.command
+                && template
+                    .components()
+                    .any(|c| c.as_str().starts_with("svix-lib-"))
+            {
+                tracing::warn!(
+                    %template,
+                    "no --param-rules given, the svix-lib templates expect \
+                     templates/svix-param-rules.json"
+                );
+            }
+            ParamRules::default()
+        }
+    };
 
     let input_files = match &args.command {
         Command::Generate { input_file, .. } => input_file,
         Command::Debug { input_file } => input_file,
     };
 
-    let api = input_files
+    let mut api = input_files
         .iter()
         .try_fold(Api::default(), |result, input_file| {
             let input_file = Path::new(input_file);
@@ -117,21 +155,17 @@ pub fn run_cli_v1_main() -> anyhow::Result<()> {
             let input_file_contents = fs::read_to_string(input_file)?;
 
             let api = if input_file_ext == "json" {
-                let spec: OpenApi = serde_json::from_str(&input_file_contents)
+                let spec = serde_json::from_str(&input_file_contents)
                     .context("failed to parse OpenAPI spec")?;
 
-                let webhooks = if args.include_webhooks {
-                    get_webhooks(&spec)
-                } else {
-                    vec![]
-                };
                 Api::new(
-                    spec.paths.context("found no endpoints in input spec")?,
-                    spec.components.unwrap_or_default(),
-                    &webhooks,
+                    spec,
+                    args.include_webhooks,
                     args.include_mode,
                     &excluded_operations,
                     &specified_operations,
+                    &param_rules,
+                    args.strict,
                 )
                 .context("converting OpenAPI spec to our own representation")?
             } else if input_file_ext == "ron" {
@@ -142,6 +176,10 @@ pub fn run_cli_v1_main() -> anyhow::Result<()> {
 
             result.merge(api)
         })?;
+    if args.split_read_write {
+        api.split_read_write()
+            .context("splitting types into input and output types")?;
+    }
 
     match args.command {
         Command::Generate {
@@ -210,45 +248,6 @@ pub fn run_cli_v1_main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn get_webhooks(spec: &OpenApi) -> Vec<String> {
-    let empty_obj = serde_json::Map::new();
-    let mut referenced_components = std::collections::BTreeSet::<String>::new();
-    if let Some(webhooks) = spec.extensions.get("x-webhooks") {
-        for req in webhooks.as_object().unwrap_or(&empty_obj).values() {
-            for method in req.as_object().unwrap_or(&empty_obj).values() {
-                if let Some(schema_ref) =
-                    method["requestBody"]["content"]["application/json"]["schema"]["$ref"].as_str()
-                    && let Some(schema_name) = schema_ref.split('/').next_back()
-                {
-                    referenced_components.insert(schema_name.to_string());
-                }
-            }
-        }
-    }
-
-    // also check the spec.webhooks
-    for (_, webhook) in &spec.webhooks {
-        let Some(item) = webhook.as_item() else {
-            continue;
-        };
-
-        for (_, op) in item.iter() {
-            if let Some(body) = &op.request_body
-                && let Some(item) = body.as_item()
-                && let Some(json_content) = item.content.get("application/json")
-                && let Some(schema) = &json_content.schema
-                && let Some(reference_v) = &schema.json_schema.get("$ref")
-                && let Some(reference) = reference_v.as_str()
-                && let Some(component_name) = reference.split('/').next_back()
-            {
-                referenced_components.insert(component_name.to_owned());
-            }
-        }
-    }
-
-    referenced_components.into_iter().collect::<Vec<String>>()
-}
-
 pub struct ExitOnErrorLayer {
     error_occurred: Arc<AtomicBool>,
 }
@@ -7,14 +7,12 @@ use std::{
 use crate::{
     CodegenLanguage,
     api::{
-        Api, Resource,
+        Api, ParamRules, Resource,
         types::{EnumVariantType, Field, FieldType, StructEnumRepr, Type, TypeData},
     },
     cli_v1::IncludeMode,
     template,
 };
-use aide::openapi::OpenApi;
-use anyhow::Context;
 use minijinja::{Value, context};
 use serde::{Serialize, Serializer};
 
@@ -50,23 +48,37 @@ fn codesample_env(
     Ok(env)
 }
 
-fn recursively_resolve_type(ty_name: &str, api: &Api) -> Type {
+/// Resolve the type `ty_name` and the types it refers to.
+///
+/// References to a type that is already being resolved further up, by a recursive type, are
+/// left unresolved.
+fn recursively_resolve_type(ty_name: &str, api: &Api, resolving: &mut Vec<String>) -> Type {
     let mut ty = api.types.get(ty_name).unwrap().clone();
+    resolving.push(ty_name.to_owned());
 
-    let update_fields = |fields: &mut Vec<Field>, api: &Api| {
+    fn resolve_ref(name: &str, api: &Api, resolving: &mut Vec<String>) -> Option<Box<Type>> {
+        if resolving.iter().any(|n| n == name) {
+            return None;
+        }
+        Some(Box::new(recursively_resolve_type(name, api, resolving)))
+    }
+    let update_field_type = |ty: &mut FieldType, resolving: &mut Vec<String>| {
+        if let FieldType::SchemaRef { name, .. } = ty {
+            let inner = resolve_ref(name, api, resolving);
+            *ty = FieldType::SchemaRef {
+                name: name.clone(),
+                inner,
+            };
+        }
+    };
+    let update_fields = |fields: &mut Vec<Field>, resolving: &mut Vec<String>| {
         for f in fields.iter_mut() {
-            if let FieldType::SchemaRef { name, .. } = &f.r#type {
-                let inner_ty = recursively_resolve_type(name, api);
-                f.r#type = FieldType::SchemaRef {
-                    name: name.clone(),
-                    inner: Some(inner_ty),
-                };
-            }
+            update_field_type(&mut f.r#type, resolving);
         }
     };
     match ty.data {
-        TypeData::Struct { ref mut fields } => {
-            update_fields(fields, api);
+        TypeData::Struct { ref mut fields, .. } => {
+            update_fields(fields, resolving);
         }
         TypeData::StringEnum { .. } => (),
         TypeData::IntegerEnum { .. } => (),
@@ -76,16 +88,19 @@ fn recursively_resolve_type(ty_name: &str, api: &Api) -> Type {
             ..
         } => {
             match repr {
-                StructEnumRepr::AdjacentlyTagged { variants, .. } => {
+                StructEnumRepr::AdjacentlyTagged { variants, .. }
+                | StructEnumRepr::InternallyTagged { variants, .. }
+                | StructEnumRepr::ExternallyTagged { variants } => {
                     for v in variants.iter_mut() {
                         match &mut v.content {
                             EnumVariantType::Struct { fields } => {
-                                update_fields(fields, api);
+                                update_fields(fields, resolving);
                             }
-                            EnumVariantType::Ref { schema_ref, inner } => {
+                            EnumVariantType::Ref {
+                                schema_ref, inner, ..
+                            } => {
                                 if let Some(schema_ref) = schema_ref {
-                                    let inner_ty = recursively_resolve_type(schema_ref, api);
-                                    *inner = Some(inner_ty);
+                                    *inner = resolve_ref(schema_ref, api, resolving);
                                 }
                             }
                         }
@@ -93,9 +108,21 @@ fn recursively_resolve_type(ty_name: &str, api: &Api) -> Type {
                 }
             }
 
-            update_fields(fields, api);
+            update_fields(fields, resolving);
+        }
+        TypeData::Union {
+            ref mut members, ..
+        } => {
+            for m in members.iter_mut() {
+                update_field_type(&mut m.r#type, resolving);
+            }
+        }
+        TypeData::Alias { ref mut target } => {
+            update_field_type(target, resolving);
         }
     }
+
+    resolving.pop();
     ty
 }
 
@@ -117,7 +144,7 @@ fn generate_sample(
             let req_body_ty = operation
                 .request_body_schema_name
                 .as_ref()
-                .map(|req_body_name| recursively_resolve_type(req_body_name, api));
+                .map(|req_body_name| recursively_resolve_type(req_body_name, api, &mut vec![]));
             let ctx = context! { api, operation, resource_parents, req_body_ty };
 
             let codesample = env.render_str(&source, ctx).unwrap();
@@ -202,22 +229,25 @@ impl CodesampleTemplates {
     }
 }
 
+/// Generate code samples for the operations of `openapi_spec`.
+///
+/// The spec is taken as JSON rather than as an `aide` `OpenApi`, which can't tell an operation's
+/// `security: []` from a missing `security`.
 pub async fn generate_codesamples(
-    openapi_spec: &OpenApi,
+    openapi_spec: serde_json::Value,
     templates: CodesampleTemplates,
     excluded_operation_ids: BTreeSet<String>,
+    param_rules: &ParamRules,
     path_param_example: fn(String) -> String,
 ) -> anyhow::Result<BTreeMap<CodegenLanguage, Vec<CodeSample>>> {
     let api_ir = crate::api::Api::new(
-        openapi_spec
-            .paths
-            .clone()
-            .context("found no endpoints in input spec")?,
-        openapi_spec.components.clone().unwrap_or_default(),
-        &[],
+        openapi_spec,
+        false,
         IncludeMode::Public,
         &excluded_operation_ids,
         &BTreeSet::new(),
+        param_rules,
+        false,
     )?;
 
     let mut samples_map = BTreeMap::new();
@@ -21,6 +21,8 @@ enum TemplateKind {
     OperationOptions,
     Type,
     Summary,
+    Webhook,
+    WebhookSummary,
 }
 
 pub fn generate(
@@ -46,9 +48,12 @@ pub fn generate(
         "operation_options" => TemplateKind::OperationOptions,
         "api_summary" | "component_type_summary" | "summary" => TemplateKind::Summary,
         "component_type" => TemplateKind::Type,
+        "webhook" => TemplateKind::Webhook,
+        "webhook_summary" => TemplateKind::WebhookSummary,
         _ => bail!(
             "template file basename must be one of 'api_resource', 'api_summary', \
-             'component_type', 'component_type_summary', 'summary'",
+             'component_type', 'component_type_summary', 'summary', 'webhook', \
+             'webhook_summary'",
         ),
     };
 
@@ -76,6 +81,8 @@ pub fn generate(
         TemplateKind::ApiResource => generator.generate_api_resources(api)?,
         TemplateKind::Type => generator.generate_types(api, output_dir)?,
         TemplateKind::Summary => generator.generate_summary(api)?,
+        TemplateKind::Webhook => generator.generate_webhooks(api)?,
+        TemplateKind::WebhookSummary => generator.generate_webhook_summary(api)?,
     };
 
     if !no_postprocess {
@@ -167,6 +174,25 @@ impl Generator<'_> {
         self.render_tpl(None, context! { api })
     }
 
+    fn generate_webhooks(&self, api: &Api) -> anyhow::Result<Vec<Utf8PathBuf>> {
+        let mut generated_paths = vec![];
+
+        for webhook in &api.webhooks {
+            let referenced_components = [&webhook.payload_schema_name];
+            generated_paths.extend_from_slice(&self.render_tpl(
+                Some(&webhook.name),
+                context! { api, webhook, referenced_components },
+            )?);
+        }
+
+        Ok(generated_paths)
+    }
+
+    fn generate_webhook_summary(&self, api: &Api) -> anyhow::Result<Vec<Utf8PathBuf>> {
+        let webhooks = &api.webhooks;
+        self.render_tpl(Some("webhooks"), context! { api, webhooks })
+    }
+
     fn render_tpl(
         &self,
         output_name: Option<&str>,
@@ -1,8 +1,8 @@
-use std::sync::LazyLock;
+use std::{collections::BTreeMap, sync::LazyLock};
 
 use anyhow::Context as _;
 
-use crate::{JsonObject, JsonValue};
+use crate::{JsonObject, JsonValue, api::Extensions};
 
 pub(crate) fn get_properties(obj: &JsonValue) -> anyhow::Result<&JsonObject> {
     static EMPTY_OBJECT: LazyLock<JsonObject> = LazyLock::new(JsonObject::new);
@@ -12,3 +12,14 @@ pub(crate) fn get_properties(obj: &JsonValue) -> anyhow::Result<&JsonObject> {
         None => Ok(&EMPTY_OBJECT),
     }
 }
+
+pub(crate) fn get_extensions(obj: &JsonValue) -> Extensions {
+    let Some(obj) = obj.as_object() else {
+        return BTreeMap::new();
+    };
+
+    obj.iter()
+        .filter(|(key, _)| key.starts_with("x-"))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}